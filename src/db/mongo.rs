@@ -1,15 +1,25 @@
 use mongodb::{
     bson::doc, 
-    options::{ClientOptions, ServerApi, ServerApiVersion}, 
-    Client, Database
+    options::{ClientOptions, IndexOptions, ServerApi, ServerApiVersion}, 
+    Client, Database, IndexModel
 };
+use futures::TryStreamExt;
 use std::env;
 
-// Allow dead_code warnings since these will be used in the future
-#[allow(dead_code)]
+// What happened when a snapshot was saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    // A new version was added to the history
+    Inserted,
+    // The stored copy of the same version was replaced
+    Replaced,
+    // The same version with identical content was already stored; it was
+    // only marked current
+    Unchanged,
+}
+
 #[derive(Clone)]
 pub struct MongoDb {
-    pub client: Client,
     pub database: Database,
 }
 
@@ -62,9 +72,10 @@ impl MongoDb {
         
         println!("Pinged your deployment. You successfully connected to MongoDB!");
         
-        Ok(MongoDb { client, database })
+        Ok(MongoDb { database })
     }
 
+    // Plain insert for collections that are not versioned snapshots
     pub async fn insert_into_collection<T>(
         &self,
         collection_name: &str,
@@ -91,15 +102,245 @@ impl MongoDb {
         let document = collection.find_one(filter).await?;
         Ok(document)
     }
+
+    pub async fn get_documents_from_collection<T>(
+        &self,
+        collection_name: &str,
+        filter: mongodb::bson::Document,
+    ) -> Result<Vec<T>, mongodb::error::Error>
+    where
+        T: serde::de::DeserializeOwned + Send + Sync,
+    {
+        let collection = self.database.collection(collection_name);
+        let cursor = collection.find(filter).await?;
+        cursor.try_collect().await
+    }
+
+    // One page of matching documents in `sort` order. The projection can
+    // leave out large fields.
+    pub async fn get_page_from_collection<T>(
+        &self,
+        collection_name: &str,
+        filter: mongodb::bson::Document,
+        sort: mongodb::bson::Document,
+        projection: mongodb::bson::Document,
+        skip: u64,
+        limit: i64,
+    ) -> Result<Vec<T>, mongodb::error::Error>
+    where
+        T: serde::de::DeserializeOwned + Send + Sync,
+    {
+        let collection = self.database.collection(collection_name);
+        let cursor = collection
+            .find(filter)
+            .sort(sort)
+            .projection(projection)
+            .skip(skip)
+            .limit(limit)
+            .await?;
+        cursor.try_collect().await
+    }
+
+    // Distinct string values of a field across the matching documents
+    pub async fn distinct_in_collection(
+        &self,
+        collection_name: &str,
+        field: &str,
+        filter: mongodb::bson::Document,
+    ) -> Result<Vec<String>, mongodb::error::Error> {
+        let collection = self.database.collection::<mongodb::bson::Document>(collection_name);
+        let values = collection.distinct(field, filter).await?;
+        Ok(values
+            .into_iter()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect())
+    }
+
+    // Newest document matching the filter, ordered by a timestamp field
+    pub async fn get_latest_document_from_collection<T>(
+        &self,
+        collection_name: &str,
+        filter: mongodb::bson::Document,
+        sort_field: &str,
+    ) -> Result<Option<T>, mongodb::error::Error>
+    where
+        T: serde::de::DeserializeOwned + Send + Sync,
+    {
+        let collection = self.database.collection(collection_name);
+        let document = collection
+            .find_one(filter)
+            .sort(doc! { sort_field: -1 })
+            .await?;
+        Ok(document)
+    }
+
+    // Store one snapshot in a versioned history.
+    //
+    // Documents matching `key` (e.g. file_key, or file_key + node_id) form one
+    // history. A snapshot of the same version with the same content hash is
+    // reused, the same version with new content is replaced, anything else is
+    // added. A new version keeps its own row even when its content matches an
+    // older one, so it can be looked up by version.
+    // With `make_current` the saved snapshot is flagged `current: true` and
+    // all others false; otherwise it is stored as history only.
+    pub async fn upsert_snapshot<T>(
+        &self,
+        collection_name: &str,
+        key: mongodb::bson::Document,
+        version: Option<&str>,
+        content_hash: &str,
+        document: &T,
+        make_current: bool,
+    ) -> Result<SnapshotOutcome, mongodb::error::Error>
+    where
+        T: serde::Serialize + Send + Sync,
+    {
+        let collection = self.database.collection::<mongodb::bson::Document>(collection_name);
+
+        let mut same_version = key.clone();
+        same_version.insert("version", version);
+        let mut same_content = same_version.clone();
+        same_content.insert("content_hash", content_hash);
+
+        let (id, outcome) = match collection.find_one(same_content).await? {
+            Some(existing) => {
+                let id = existing.get("_id").cloned().unwrap_or(mongodb::bson::Bson::Null);
+                let mut update = doc! { "fetched_at": mongodb::bson::DateTime::now() };
+                if make_current {
+                    update.insert("current", true);
+                }
+                collection
+                    .update_one(doc! { "_id": id.clone() }, doc! { "$set": update })
+                    .await?;
+                (id, SnapshotOutcome::Unchanged)
+            }
+            None => {
+                // History writes must not demote a snapshot that is current
+                let current = make_current
+                    || collection
+                        .find_one(same_version.clone())
+                        .await?
+                        .and_then(|d| d.get_bool("current").ok())
+                        .unwrap_or(false);
+                let mut replacement = mongodb::bson::to_document(document)?;
+                replacement.insert("current", current);
+                let res = collection
+                    .replace_one(same_version.clone(), replacement)
+                    .upsert(true)
+                    .await?;
+                match res.upserted_id {
+                    Some(id) => (id, SnapshotOutcome::Inserted),
+                    None => {
+                        let id = collection
+                            .find_one(same_version)
+                            .await?
+                            .and_then(|d| d.get("_id").cloned())
+                            .unwrap_or(mongodb::bson::Bson::Null);
+                        (id, SnapshotOutcome::Replaced)
+                    }
+                }
+            }
+        };
+
+        if !make_current {
+            println!("Saved history snapshot in {}: {:?}", collection_name, outcome);
+            return Ok(outcome);
+        }
+
+        // Everything else in this history is now an older version
+        let mut previous = key;
+        previous.insert("current", true);
+        previous.insert("_id", doc! { "$ne": id });
+        collection
+            .update_many(previous, doc! { "$set": { "current": false } })
+            .await?;
+
+        println!("Saved snapshot in {}: {:?}", collection_name, outcome);
+        Ok(outcome)
+    }
+
+    // Replace the document matching the filter, inserting it if missing
+    pub async fn replace_in_collection<T>(
+        &self,
+        collection_name: &str,
+        filter: mongodb::bson::Document,
+        document: &T,
+    ) -> Result<(), mongodb::error::Error>
+    where
+        T: serde::Serialize + Send + Sync,
+    {
+        let collection = self.database.collection::<mongodb::bson::Document>(collection_name);
+        let replacement = mongodb::bson::to_document(document)?;
+        collection.replace_one(filter, replacement).upsert(true).await?;
+        Ok(())
+    }
+
+    pub async fn update_in_collection(
+        &self,
+        collection_name: &str,
+        filter: mongodb::bson::Document,
+        update: mongodb::bson::Document,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = self.database.collection::<mongodb::bson::Document>(collection_name);
+        let res = collection.update_many(filter, update).await?;
+        Ok(res.modified_count)
+    }
     
     
     
-    // This method will be used in the future as your app grows
-    #[allow(dead_code)]
-    pub fn collection<T>(&self, name: &str) -> mongodb::Collection<T> 
-    where 
-        T: serde::de::DeserializeOwned + serde::Serialize + Send + Sync,
-    {
-        self.database.collection(name)
+    // Delete all but one document per combination of `keys`, keeping the
+    // first in `keep` order, so a unique index can be built. Returns how many
+    // documents were removed.
+    pub async fn remove_duplicates(
+        &self,
+        collection_name: &str,
+        keys: &[&str],
+        keep: mongodb::bson::Document,
+    ) -> Result<u64, mongodb::error::Error> {
+        let collection = self.database.collection::<mongodb::bson::Document>(collection_name);
+        let mut group = mongodb::bson::Document::new();
+        for key in keys {
+            group.insert(*key, format!("${}", key));
+        }
+        let pipeline = vec![
+            doc! { "$sort": keep },
+            doc! { "$group": { "_id": group, "ids": { "$push": "$_id" }, "count": { "$sum": 1 } } },
+            doc! { "$match": { "count": { "$gt": 1 } } },
+        ];
+        let mut duplicates = collection.aggregate(pipeline).await?;
+        let mut removed = 0;
+        while let Some(duplicate) = duplicates.try_next().await? {
+            let Ok(ids) = duplicate.get_array("ids") else { continue };
+            let res = collection
+                .delete_many(doc! { "_id": { "$in": ids[1..].to_vec() } })
+                .await?;
+            removed += res.deleted_count;
+        }
+        Ok(removed)
     }
-}
\ No newline at end of file
+    
+    // Unique index on the given keys; a no-op when it already exists
+    pub async fn create_unique_index(
+        &self,
+        collection_name: &str,
+        keys: mongodb::bson::Document,
+    ) -> Result<(), mongodb::error::Error> {
+        let collection = self.database.collection::<mongodb::bson::Document>(collection_name);
+        let index = IndexModel::builder()
+            .keys(keys)
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+        collection.create_index(index).await?;
+        Ok(())
+    }
+
+    // A handle for tests. Nothing listens on the discard port, so every
+    // operation fails quickly instead of waiting for a server.
+    #[cfg(test)]
+    pub async fn unreachable() -> Self {
+        let client = Client::with_uri_str("mongodb://127.0.0.1:9/?serverSelectionTimeoutMS=10")
+            .await
+            .unwrap();
+        MongoDb { database: client.database("ui_forge_test") }
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{Duration, Utc};
+use mongodb::bson::doc;
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::db::mongo::MongoDb;
+use crate::figma::client::{FigmaClient, FigmaError};
+use crate::figma::models::{FigmaFile, StoredFile};
+
+// Response header describing where a file came from
+pub const CACHE_HEADER: &str = "X-Figma-Cache";
+
+// How getFile may use the copy stored in figma_file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheMode {
+    // Always download from Figma
+    Bypass,
+    // Serve the stored copy while it is unchanged in Figma
+    #[default]
+    Prefer,
+    // Never call Figma; 404 when nothing is stored
+    Only,
+}
+
+// Outcome reported in the X-Figma-Cache header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    // Stored copy served within its TTL
+    Hit,
+    // Stored copy served after Figma confirmed it is unchanged
+    Revalidated,
+    // Stored copy missing or stale, downloaded again
+    Miss,
+    // Cache skipped at the caller's request
+    Bypass,
+}
+
+impl CacheMode {
+    // The stored snapshot to look for before calling Figma. cache=only is
+    // refused for partial requests, which are never stored.
+    pub fn lookup(self, snapshot: Option<&Snapshot>) -> Result<Option<&Snapshot>, &'static str> {
+        match (self, snapshot) {
+            (CacheMode::Only, None) => {
+                Err("cache=only cannot be combined with ids, depth, geometry, plugin_data or branch_data")
+            }
+            (CacheMode::Bypass, _) => Ok(None),
+            (_, snapshot) => Ok(snapshot),
+        }
+    }
+
+    // X-Figma-Cache of a response downloaded from Figma
+    pub fn download_status(self, snapshot: Option<&Snapshot>) -> CacheStatus {
+        if snapshot.is_some() && self != CacheMode::Bypass {
+            CacheStatus::Miss
+        } else {
+            CacheStatus::Bypass
+        }
+    }
+}
+
+// The stored snapshot a getFile request is answered from and saved as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Snapshot {
+    Current,
+    // Figma versions never change, so a stored one is always fresh
+    Version(String),
+}
+
+// Only full-file requests, optionally pinned to a version, have a snapshot
+pub fn snapshot_for(params: &HashMap<&str, String>) -> Option<Snapshot> {
+    match params.get("version") {
+        Some(version) if params.len() == 1 => Some(Snapshot::Version(version.clone())),
+        _ if params.is_empty() => Some(Snapshot::Current),
+        _ => None,
+    }
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Revalidated => "REVALIDATED",
+            CacheStatus::Miss => "MISS",
+            CacheStatus::Bypass => "BYPASS",
+        }
+    }
+}
+
+// A stored copy is fresh without asking Figma while it is younger than the
+// client's cache TTL
+pub fn within_ttl(ttl: std::time::Duration, stored: &StoredFile) -> bool {
+    let Ok(ttl) = Duration::from_std(ttl) else {
+        return false;
+    };
+    stored
+        .fetched_at
+        .map(|fetched_at| Utc::now() - fetched_at < ttl)
+        .unwrap_or(false)
+}
+
+// Ask Figma for the file's version with depth=1, which skips the node tree,
+// and compare it with the stored copy. Returns the retries the check took.
+pub async fn is_unchanged(
+    client: &FigmaClient,
+    token: &str,
+    file_key: &str,
+    stored: &FigmaFile,
+) -> Result<(bool, u32), FigmaError> {
+    let mut params = HashMap::new();
+    params.insert("depth", "1".to_string());
+    let meta = client.get_file(token, file_key, &params).await?;
+    Ok((same_version(&meta.data, stored), meta.retries))
+}
+
+fn same_version(meta: &FigmaFile, stored: &FigmaFile) -> bool {
+    meta.version.is_some() && meta.version == stored.version && meta.last_modified == stored.last_modified
+}
+
+// Reset the TTL of a stored copy that Figma confirmed is unchanged
+pub async fn touch(db: &MongoDb, file_key: &str) -> Result<u64, mongodb::error::Error> {
+    let filter = doc! { "file_key": file_key, "current": true };
+    let update = doc! { "$set": { "fetched_at": mongodb::bson::DateTime::now() } };
+    db.update_in_collection("figma_file", filter, update).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(pairs: &[(&'static str, &str)]) -> HashMap<&'static str, String> {
+        pairs.iter().map(|(key, value)| (*key, value.to_string())).collect()
+    }
+
+    fn stored(fetched_at: Option<chrono::DateTime<Utc>>) -> StoredFile {
+        StoredFile {
+            file_key: "abc".to_string(),
+            message: String::new(),
+            status: "success".to_string(),
+            data: None,
+            fetched_at,
+            version: None,
+            content_hash: None,
+            current: true,
+        }
+    }
+
+    fn file(version: Option<&str>, last_modified: &str) -> FigmaFile {
+        serde_json::from_value(json!({
+            "name": "Test",
+            "version": version,
+            "lastModified": last_modified,
+            "document": { "id": "0:0", "type": "DOCUMENT" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn only_whole_files_have_a_snapshot() {
+        assert_eq!(snapshot_for(&params(&[])), Some(Snapshot::Current));
+        assert_eq!(snapshot_for(&params(&[("version", "42")])), Some(Snapshot::Version("42".to_string())));
+        assert_eq!(snapshot_for(&params(&[("version", "42"), ("depth", "1")])), None);
+        assert_eq!(snapshot_for(&params(&[("ids", "1:2")])), None);
+    }
+
+    #[test]
+    fn modes_decide_which_snapshot_is_read() {
+        let current = Snapshot::Current;
+        assert_eq!(CacheMode::Prefer.lookup(Some(&current)), Ok(Some(&current)));
+        assert_eq!(CacheMode::Only.lookup(Some(&current)), Ok(Some(&current)));
+        assert_eq!(CacheMode::Bypass.lookup(Some(&current)), Ok(None));
+        assert_eq!(CacheMode::Prefer.lookup(None), Ok(None));
+        assert_eq!(CacheMode::Bypass.lookup(None), Ok(None));
+        assert!(CacheMode::Only.lookup(None).is_err());
+    }
+
+    #[test]
+    fn downloads_are_misses_unless_the_cache_was_skipped() {
+        let current = Snapshot::Current;
+        assert_eq!(CacheMode::Prefer.download_status(Some(&current)), CacheStatus::Miss);
+        assert_eq!(CacheMode::Bypass.download_status(Some(&current)), CacheStatus::Bypass);
+        assert_eq!(CacheMode::Prefer.download_status(None), CacheStatus::Bypass);
+    }
+
+    #[test]
+    fn modes_parse_from_the_query_with_prefer_as_default() {
+        assert_eq!(serde_json::from_value::<CacheMode>(json!("only")).unwrap(), CacheMode::Only);
+        assert_eq!(serde_json::from_value::<CacheMode>(json!("bypass")).unwrap(), CacheMode::Bypass);
+        assert!(serde_json::from_value::<CacheMode>(json!("always")).is_err());
+        assert_eq!(CacheMode::default(), CacheMode::Prefer);
+    }
+
+    #[test]
+    fn stored_copies_are_fresh_within_the_ttl() {
+        let ttl = std::time::Duration::from_secs(300);
+        assert!(within_ttl(ttl, &stored(Some(Utc::now() - Duration::seconds(60)))));
+        assert!(!within_ttl(ttl, &stored(Some(Utc::now() - Duration::seconds(600)))));
+        assert!(!within_ttl(ttl, &stored(None)));
+        assert!(!within_ttl(std::time::Duration::ZERO, &stored(Some(Utc::now()))));
+    }
+
+    #[test]
+    fn revalidation_compares_version_and_modification_time() {
+        let stored = file(Some("42"), "2026-10-01T10:00:00Z");
+        assert!(same_version(&file(Some("42"), "2026-10-01T10:00:00Z"), &stored));
+        assert!(!same_version(&file(Some("43"), "2026-10-02T10:00:00Z"), &stored));
+        assert!(!same_version(&file(Some("42"), "2026-10-02T10:00:00Z"), &stored));
+        let unversioned = file(None, "2026-10-01T10:00:00Z");
+        assert!(!same_version(&unversioned, &unversioned));
+    }
+}
@@ -0,0 +1,351 @@
+use reqwest::{Client, StatusCode};
+use reqwest::header::RETRY_AFTER;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use crate::figma::config::FigmaConfig;
+use crate::figma::models::{
+    FigmaFile, FigmaFileNodes, FileVersions, LocalVariables, PublishedVariables, VariablesResponse,
+};
+use crate::figma::rate_limit::{backoff_delay, RateLimiter};
+
+#[derive(Debug, Error)]
+pub enum FigmaError {
+    #[error("Failed to build Figma client: {0}")]
+    Client(reqwest::Error),
+    #[error("Failed to call Figma API: {0}")]
+    Request(reqwest::Error),
+    #[error("Figma API request timed out")]
+    Timeout,
+    #[error("Figma API error: {body}")]
+    Api {
+        status: StatusCode,
+        body: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("Failed to parse Figma API response: {0}")]
+    Parse(reqwest::Error),
+    #[error("{source} (after {retries} retries)")]
+    RetriesExhausted {
+        retries: u32,
+        source: Box<FigmaError>,
+    },
+}
+
+impl FigmaError {
+    // Status to report to our own caller
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            FigmaError::Api { status, .. } => *status,
+            FigmaError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            FigmaError::RetriesExhausted { source, .. } => source.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // How many times the request was retried before failing
+    pub fn retries(&self) -> u32 {
+        match self {
+            FigmaError::RetriesExhausted { retries, .. } => *retries,
+            _ => 0,
+        }
+    }
+
+    // 429s, 5xx responses and failed connections are worth another attempt
+    fn is_retryable(&self) -> bool {
+        match self {
+            FigmaError::Api { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            FigmaError::Request(e) => e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FigmaError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            FigmaError::Timeout
+        } else {
+            FigmaError::Request(e)
+        }
+    }
+}
+
+// Response header telling our callers how often a Figma request was retried
+pub const RETRIES_HEADER: &str = "X-Figma-Retries";
+
+// A successful Figma response and the number of retries it took
+pub struct Fetched<T> {
+    pub data: T,
+    pub retries: u32,
+}
+
+impl<T> Fetched<T> {
+    // Append the retry count to a success message when there were any
+    pub fn describe(&self, message: String) -> String {
+        if self.retries == 0 {
+            message
+        } else {
+            format!("{} (after {} retries)", message, self.retries)
+        }
+    }
+}
+
+// Shared Figma REST client. Cloning is cheap and keeps the connection pool
+// and the per-token rate limiter.
+#[derive(Clone)]
+pub struct FigmaClient {
+    http: Client,
+    base_url: String,
+    max_retries: u32,
+    retry_base_delay: Duration,
+    retry_max_delay: Duration,
+    limiter: Arc<RateLimiter>,
+    cache_ttl: Duration,
+}
+
+impl FigmaClient {
+    pub fn new(config: FigmaConfig) -> Result<Self, FigmaError> {
+        let http = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_seconds))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .map_err(FigmaError::Client)?;
+
+        Ok(FigmaClient {
+            http,
+            base_url: config.base_url,
+            max_retries: config.max_retries,
+            retry_base_delay: Duration::from_millis(config.retry_base_delay_ms),
+            retry_max_delay: Duration::from_millis(config.retry_max_delay_ms),
+            limiter: Arc::new(RateLimiter::new(config.rate_limit_burst, config.rate_limit_per_second)),
+            cache_ttl: Duration::from_secs(config.cache_ttl_seconds),
+        })
+    }
+
+    pub fn from_env() -> Result<Self, FigmaError> {
+        Self::new(FigmaConfig::from_env())
+    }
+
+    // How long a stored file is served without asking Figma
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl
+    }
+
+    // GET {base_url}{path} and parse the JSON body, retrying throttled and
+    // failed requests with backoff
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        path: &str,
+        params: &HashMap<&str, String>,
+    ) -> Result<Fetched<T>, FigmaError> {
+        let mut retries = 0;
+        loop {
+            self.limiter.acquire(token).await;
+
+            let error = match self.send_once(token, path, params).await {
+                Ok(data) => return Ok(Fetched { data, retries }),
+                Err(e) => e,
+            };
+
+            if !error.is_retryable() || retries >= self.max_retries {
+                return Err(with_retries(error, retries));
+            }
+
+            // Honor Retry-After when Figma sends one, otherwise back off
+            let retry_after = match &error {
+                FigmaError::Api { status, retry_after, .. } => {
+                    if *status == StatusCode::TOO_MANY_REQUESTS {
+                        self.limiter.drain(token);
+                    }
+                    *retry_after
+                }
+                _ => None,
+            };
+            let delay = match retry_after {
+                Some(delay) if delay > self.retry_max_delay => {
+                    // Waiting that long would hang our caller; report it instead
+                    return Err(with_retries(error, retries));
+                }
+                Some(delay) => delay,
+                None => backoff_delay(retries, self.retry_base_delay, self.retry_max_delay),
+            };
+
+            retries += 1;
+            println!(
+                "Figma request {} failed ({}), retry {} of {} in {:?}",
+                path, error, retries, self.max_retries, delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn send_once<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        path: &str,
+        params: &HashMap<&str, String>,
+    ) -> Result<T, FigmaError> {
+        let url = format!("{}{}", self.base_url, path);
+
+        let response = self
+            .http
+            .get(url)
+            .query(params)
+            .header("X-Figma-Token", token)
+            .send()
+            .await?;
+
+        // Check status code before consuming response body
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Could not read error response".to_string());
+            return Err(FigmaError::Api { status, body, retry_after });
+        }
+
+        response.json::<T>().await.map_err(|e| {
+            if e.is_timeout() {
+                FigmaError::Timeout
+            } else {
+                FigmaError::Parse(e)
+            }
+        })
+    }
+
+    // GET /files/:key
+    pub async fn get_file(
+        &self,
+        token: &str,
+        file_key: &str,
+        params: &HashMap<&str, String>,
+    ) -> Result<Fetched<FigmaFile>, FigmaError> {
+        self.get_json(token, &format!("/files/{}", file_key), params).await
+    }
+
+    // GET /files/:key/versions
+    pub async fn get_versions(
+        &self,
+        token: &str,
+        file_key: &str,
+        params: &HashMap<&str, String>,
+    ) -> Result<Fetched<FileVersions>, FigmaError> {
+        self.get_json(token, &format!("/files/{}/versions", file_key), params).await
+    }
+
+    // GET /files/:key/nodes?ids=
+    pub async fn get_nodes(
+        &self,
+        token: &str,
+        file_key: &str,
+        ids: &str,
+    ) -> Result<Fetched<FigmaFileNodes>, FigmaError> {
+        let mut params = HashMap::new();
+        params.insert("ids", ids.to_string());
+        self.get_json(token, &format!("/files/{}/nodes", file_key), &params).await
+    }
+
+    // GET /files/:key/variables/local
+    pub async fn get_local_variables(
+        &self,
+        token: &str,
+        file_key: &str,
+    ) -> Result<Fetched<LocalVariables>, FigmaError> {
+        let fetched: Fetched<VariablesResponse<LocalVariables>> = self
+            .get_json(token, &format!("/files/{}/variables/local", file_key), &HashMap::new())
+            .await?;
+        Ok(Fetched {
+            data: fetched.data.meta,
+            retries: fetched.retries,
+        })
+    }
+
+    // GET /files/:key/variables/published
+    pub async fn get_published_variables(
+        &self,
+        token: &str,
+        file_key: &str,
+    ) -> Result<Fetched<PublishedVariables>, FigmaError> {
+        let fetched: Fetched<VariablesResponse<PublishedVariables>> = self
+            .get_json(token, &format!("/files/{}/variables/published", file_key), &HashMap::new())
+            .await?;
+        Ok(Fetched {
+            data: fetched.data.meta,
+            retries: fetched.retries,
+        })
+    }
+}
+
+// Retry-After in seconds; the HTTP date form is not used by Figma
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+fn with_retries(error: FigmaError, retries: u32) -> FigmaError {
+    if retries == 0 {
+        error
+    } else {
+        FigmaError::RetriesExhausted {
+            retries,
+            source: Box::new(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_error(status: StatusCode) -> FigmaError {
+        FigmaError::Api { status, body: "error".to_string(), retry_after: None }
+    }
+
+    #[test]
+    fn retry_after_is_read_in_seconds() {
+        assert_eq!(parse_retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2026 07:28:00 GMT"), None);
+        assert_eq!(parse_retry_after("-1"), None);
+        assert_eq!(parse_retry_after(""), None);
+    }
+
+    #[test]
+    fn throttled_and_server_errors_are_retried() {
+        assert!(api_error(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(api_error(StatusCode::BAD_GATEWAY).is_retryable());
+        assert!(!api_error(StatusCode::NOT_FOUND).is_retryable());
+        assert!(!api_error(StatusCode::FORBIDDEN).is_retryable());
+        assert!(!FigmaError::Timeout.is_retryable());
+    }
+
+    #[test]
+    fn exhausted_retries_keep_the_status_of_the_last_error() {
+        let error = with_retries(api_error(StatusCode::TOO_MANY_REQUESTS), 3);
+        assert_eq!(error.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(error.retries(), 3);
+        assert_eq!(error.to_string(), "Figma API error: error (after 3 retries)");
+
+        let error = with_retries(FigmaError::Timeout, 0);
+        assert_eq!(error.status_code(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(error.retries(), 0);
+    }
+
+    #[test]
+    fn success_messages_mention_retries_only_when_there_were_some() {
+        assert_eq!(Fetched { data: (), retries: 0 }.describe("Fetched".to_string()), "Fetched");
+        assert_eq!(Fetched { data: (), retries: 2 }.describe("Fetched".to_string()), "Fetched (after 2 retries)");
+    }
+}
@@ -0,0 +1,67 @@
+use std::env;
+
+pub struct FigmaConfig {
+    pub base_url: String,
+    pub timeout_seconds: u64,
+    pub connect_timeout_seconds: u64,
+    pub max_retries: u32,
+    pub retry_base_delay_ms: u64,
+    pub retry_max_delay_ms: u64,
+    pub rate_limit_burst: f64,
+    pub rate_limit_per_second: f64,
+    pub cache_ttl_seconds: u64,
+    // Passcode given to Figma when the webhook was registered
+    pub webhook_passcode: Option<String>,
+    // Token used to re-fetch files when a webhook fires
+    pub webhook_token: Option<String>,
+    // Rebuild packages generated from a file after it changes
+    pub webhook_regenerate: bool,
+}
+
+impl FigmaConfig {
+    pub fn from_env() -> Self {
+        FigmaConfig {
+            base_url: env::var("FIGMA_API_BASE_URL")
+                .unwrap_or_else(|_| "https://api.figma.com/v1".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            timeout_seconds: env::var("FIGMA_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            connect_timeout_seconds: env::var("FIGMA_CONNECT_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            max_retries: env::var("FIGMA_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            retry_base_delay_ms: env::var("FIGMA_RETRY_BASE_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(500),
+            retry_max_delay_ms: env::var("FIGMA_RETRY_MAX_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30_000),
+            rate_limit_burst: env::var("FIGMA_RATE_LIMIT_BURST")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10.0),
+            rate_limit_per_second: env::var("FIGMA_RATE_LIMIT_PER_SECOND")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2.0),
+            cache_ttl_seconds: env::var("FIGMA_CACHE_TTL_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(300),
+            webhook_passcode: env::var("FIGMA_WEBHOOK_PASSCODE").ok().filter(|v| !v.is_empty()),
+            webhook_token: env::var("FIGMA_WEBHOOK_TOKEN").ok().filter(|v| !v.is_empty()),
+            webhook_regenerate: env::var("FIGMA_WEBHOOK_REGENERATE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use async_graphql::SimpleObject;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+use crate::db::mongo::MongoDb;
+use crate::figma::models::Node;
+use crate::figma::store;
+
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error("No stored snapshot for {0}")]
+    Missing(String),
+    #[error("Database error: {0}")]
+    Database(#[from] mongodb::error::Error),
+}
+
+// Structural differences between two snapshots of the same file or node
+#[derive(Debug, Clone, Serialize, SimpleObject)]
+pub struct SnapshotDiff {
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    pub added: Vec<NodeSummary>,
+    pub removed: Vec<NodeSummary>,
+    pub moved: Vec<MovedNode>,
+    pub changed: Vec<NodeChange>,
+}
+
+#[derive(Debug, Clone, Serialize, SimpleObject)]
+pub struct NodeSummary {
+    pub id: String,
+    pub name: String,
+    pub node_type: String,
+    pub parent_id: Option<String>,
+}
+
+// A node that changed parent or position among its siblings
+#[derive(Debug, Clone, Serialize, SimpleObject)]
+pub struct MovedNode {
+    pub id: String,
+    pub name: String,
+    pub from_parent_id: Option<String>,
+    pub to_parent_id: Option<String>,
+    pub from_index: usize,
+    pub to_index: usize,
+}
+
+#[derive(Debug, Clone, Serialize, SimpleObject)]
+pub struct NodeChange {
+    pub id: String,
+    pub name: String,
+    pub node_type: String,
+    pub changes: Vec<PropertyChange>,
+}
+
+// One property whose value differs; a missing side means the property was
+// added or removed
+#[derive(Debug, Clone, Serialize, SimpleObject)]
+pub struct PropertyChange {
+    pub property: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+// Where a node sits in its tree
+struct Placement<'a> {
+    node: &'a Node,
+    parent_id: Option<String>,
+    index: usize,
+}
+
+// Diff two stored snapshots of a file, or of one node when node_id is given.
+// `to` None compares against the current snapshot.
+pub async fn diff_stored(
+    db: &MongoDb,
+    file_key: &str,
+    from: &str,
+    to: Option<&str>,
+    node_id: Option<&str>,
+) -> Result<SnapshotDiff, DiffError> {
+    let describe = |version: Option<&str>| {
+        format!(
+            "file {}{} at {}",
+            file_key,
+            node_id.map(|id| format!(" node {}", id)).unwrap_or_default(),
+            version.map(|v| format!("version {}", v)).unwrap_or_else(|| "current version".to_string()),
+        )
+    };
+
+    let before = store::snapshot_node(db, file_key, Some(from), node_id)
+        .await?
+        .ok_or_else(|| DiffError::Missing(describe(Some(from))))?;
+    let after = store::snapshot_node(db, file_key, to, node_id)
+        .await?
+        .ok_or_else(|| DiffError::Missing(describe(to)))?;
+
+    Ok(diff_nodes(&before, &after, Some(from.to_string()), to.map(str::to_string)))
+}
+
+pub fn diff_nodes(
+    before: &Node,
+    after: &Node,
+    from_version: Option<String>,
+    to_version: Option<String>,
+) -> SnapshotDiff {
+    let mut old = HashMap::new();
+    index_tree(before, None, 0, &mut old);
+    let mut new = HashMap::new();
+    index_tree(after, None, 0, &mut new);
+
+    // Inserting or deleting a sibling shifts indices, so reorders are judged
+    // only among siblings that kept their parent
+    let old_order = stable_positions(before, &new);
+    let new_order = stable_positions(after, &old);
+
+    let old_properties = own_properties(before);
+    let new_properties = own_properties(after);
+    let no_properties = serde_json::Map::new();
+
+    // Walk in document order so the output reads top to bottom
+    let mut added = Vec::new();
+    let mut moved = Vec::new();
+    let mut changed = Vec::new();
+    for id in document_order(after) {
+        let current = &new[id.as_str()];
+        let Some(previous) = old.get(id.as_str()) else {
+            added.push(summary(current));
+            continue;
+        };
+
+        if previous.parent_id != current.parent_id || old_order.get(&id) != new_order.get(&id) {
+            moved.push(MovedNode {
+                id: id.clone(),
+                name: current.node.name.clone(),
+                from_parent_id: previous.parent_id.clone(),
+                to_parent_id: current.parent_id.clone(),
+                from_index: previous.index,
+                to_index: current.index,
+            });
+        }
+
+        let changes = property_changes(
+            old_properties.get(&id).unwrap_or(&no_properties),
+            new_properties.get(&id).unwrap_or(&no_properties),
+        );
+        if !changes.is_empty() {
+            changed.push(NodeChange {
+                id: id.clone(),
+                name: current.node.name.clone(),
+                node_type: current.node.node_type.as_str().to_string(),
+                changes,
+            });
+        }
+    }
+
+    let removed = document_order(before)
+        .into_iter()
+        .filter(|id| !new.contains_key(id.as_str()))
+        .map(|id| summary(&old[id.as_str()]))
+        .collect();
+
+    SnapshotDiff {
+        from_version,
+        to_version,
+        added,
+        removed,
+        moved,
+        changed,
+    }
+}
+
+fn index_tree<'a>(
+    node: &'a Node,
+    parent_id: Option<String>,
+    index: usize,
+    out: &mut HashMap<&'a str, Placement<'a>>,
+) {
+    out.insert(node.id.as_str(), Placement { node, parent_id, index });
+    for (i, child) in node.children.iter().enumerate() {
+        index_tree(child, Some(node.id.clone()), i, out);
+    }
+}
+
+// Position of each node among its siblings that sit under the same parent
+// in `other` as well
+fn stable_positions(node: &Node, other: &HashMap<&str, Placement>) -> HashMap<String, usize> {
+    let mut positions = HashMap::new();
+    let shared = node.children.iter().filter(|child| {
+        other
+            .get(child.id.as_str())
+            .is_some_and(|placement| placement.parent_id.as_deref() == Some(node.id.as_str()))
+    });
+    for (i, child) in shared.enumerate() {
+        positions.insert(child.id.clone(), i);
+    }
+    for child in &node.children {
+        positions.extend(stable_positions(child, other));
+    }
+    positions
+}
+
+fn document_order(node: &Node) -> Vec<String> {
+    let mut ids = vec![node.id.clone()];
+    for child in &node.children {
+        ids.extend(document_order(child));
+    }
+    ids
+}
+
+fn summary(placement: &Placement) -> NodeSummary {
+    NodeSummary {
+        id: placement.node.id.clone(),
+        name: placement.node.name.clone(),
+        node_type: placement.node.node_type.as_str().to_string(),
+        parent_id: placement.parent_id.clone(),
+    }
+}
+
+// Compare every property except the children, which are diffed structurally
+fn property_changes(
+    before: &serde_json::Map<String, Value>,
+    after: &serde_json::Map<String, Value>,
+) -> Vec<PropertyChange> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .map(|key| PropertyChange {
+            property: key.clone(),
+            before: before.get(key).cloned(),
+            after: after.get(key).cloned(),
+        })
+        .collect()
+}
+
+// Properties of every node in the tree by id, without its children. The
+// tree is serialized once and split up, rather than once per node.
+fn own_properties(root: &Node) -> HashMap<String, serde_json::Map<String, Value>> {
+    let mut out = HashMap::new();
+    if let Ok(value) = serde_json::to_value(root) {
+        split_properties(value, &mut out);
+    }
+    out
+}
+
+fn split_properties(value: Value, out: &mut HashMap<String, serde_json::Map<String, Value>>) {
+    let Value::Object(mut map) = value else { return };
+    if let Some(Value::Array(children)) = map.remove("children") {
+        for child in children {
+            split_properties(child, out);
+        }
+    }
+    if let Some(id) = map.get("id").and_then(Value::as_str) {
+        out.insert(id.to_string(), map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tree(document: Value) -> Node {
+        serde_json::from_value(document).unwrap()
+    }
+
+    fn node(id: &str, children: Vec<Value>) -> Value {
+        json!({ "id": id, "name": format!("Layer {}", id), "type": "FRAME", "children": children })
+    }
+
+    fn ids<T>(items: &[T], id: impl Fn(&T) -> &str) -> Vec<String> {
+        items.iter().map(|item| id(item).to_string()).collect()
+    }
+
+    #[test]
+    fn identical_trees_have_no_differences() {
+        let root = tree(node("1", vec![node("2", vec![]), node("3", vec![])]));
+        let diff = diff_nodes(&root, &root.clone(), Some("1".to_string()), None);
+        assert_eq!(diff.from_version.as_deref(), Some("1"));
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert!(diff.moved.is_empty() && diff.changed.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_nodes_are_listed_in_document_order() {
+        let before = tree(node("1", vec![node("2", vec![node("4", vec![])]), node("3", vec![])]));
+        let after = tree(node("1", vec![node("5", vec![node("6", vec![])]), node("3", vec![])]));
+        let diff = diff_nodes(&before, &after, None, None);
+
+        assert_eq!(ids(&diff.added, |n| &n.id), ["5", "6"]);
+        assert_eq!(diff.added[1].parent_id.as_deref(), Some("5"));
+        assert_eq!(ids(&diff.removed, |n| &n.id), ["2", "4"]);
+        assert_eq!(diff.removed[0].node_type, "FRAME");
+        assert!(diff.moved.is_empty());
+    }
+
+    #[test]
+    fn inserting_a_sibling_is_not_a_move() {
+        let before = tree(node("1", vec![node("2", vec![]), node("3", vec![])]));
+        let after = tree(node("1", vec![node("9", vec![]), node("2", vec![]), node("3", vec![])]));
+        let diff = diff_nodes(&before, &after, None, None);
+        assert_eq!(ids(&diff.added, |n| &n.id), ["9"]);
+        assert!(diff.moved.is_empty());
+    }
+
+    #[test]
+    fn reordered_and_reparented_nodes_are_moves() {
+        let before = tree(node("1", vec![node("2", vec![node("4", vec![])]), node("3", vec![])]));
+        let after = tree(node("1", vec![node("3", vec![node("4", vec![])]), node("2", vec![])]));
+        let diff = diff_nodes(&before, &after, None, None);
+
+        assert_eq!(ids(&diff.moved, |n| &n.id), ["3", "4", "2"]);
+        let reparented = &diff.moved[1];
+        assert_eq!(reparented.from_parent_id.as_deref(), Some("2"));
+        assert_eq!(reparented.to_parent_id.as_deref(), Some("3"));
+        assert_eq!((diff.moved[0].from_index, diff.moved[0].to_index), (1, 0));
+    }
+
+    #[test]
+    fn property_changes_leave_out_the_children() {
+        let before = tree(json!({
+            "id": "1",
+            "name": "Card",
+            "type": "FRAME",
+            "opacity": 0.5,
+            "children": [{ "id": "2", "name": "Label", "type": "TEXT", "characters": "Hi" }],
+        }));
+        let after = tree(json!({
+            "id": "1",
+            "name": "Card",
+            "type": "FRAME",
+            "cornerRadius": 4.0,
+            "children": [
+                { "id": "2", "name": "Label", "type": "TEXT", "characters": "Hello" },
+                { "id": "3", "type": "RECTANGLE" },
+            ],
+        }));
+        let diff = diff_nodes(&before, &after, None, None);
+
+        assert_eq!(ids(&diff.changed, |n| &n.id), ["1", "2"]);
+        let root = &diff.changed[0].changes;
+        assert_eq!(ids(root, |c| &c.property), ["cornerRadius", "opacity"]);
+        assert_eq!(root[0].before, None);
+        assert_eq!(root[0].after, Some(json!(4.0)));
+        assert_eq!(root[1].before, Some(json!(0.5)));
+        assert_eq!(root[1].after, None);
+        let label = &diff.changed[1].changes;
+        assert_eq!(ids(label, |c| &c.property), ["characters"]);
+        assert_eq!(label[0].after, Some(json!("Hello")));
+    }
+}
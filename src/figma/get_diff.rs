@@ -0,0 +1,65 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use crate::figma::diff::{diff_stored, DiffError, SnapshotDiff};
+
+// Struct for query parameters
+#[derive(Deserialize)]
+pub struct DiffParams {
+    file_key: Option<String>,
+    from: Option<String>,     // Figma version of the older snapshot
+    to: Option<String>,       // defaults to the current snapshot
+    node_id: Option<String>,  // diff a single node instead of the whole file
+}
+
+// Struct for returning response
+#[derive(Serialize)]
+pub struct ApiResponse {
+    file_key: String,
+    message: String,
+    status: String,
+    data: Option<SnapshotDiff>,
+}
+
+// GET handler that diffs two stored snapshots; Figma itself is not called
+#[get("/diff")]
+pub async fn get_diff(
+    query: web::Query<DiffParams>,
+    db: web::Data<crate::db::mongo::MongoDb>,
+) -> impl Responder {
+    let (file_key, from) = match (&query.file_key, &query.from) {
+        (Some(file_key), Some(from)) => (file_key.clone(), from.clone()),
+        _ => return HttpResponse::BadRequest().json(ApiResponse {
+            file_key: query.file_key.clone().unwrap_or_default(),
+            message: "Missing file_key or from parameter".to_string(),
+            status: "error".to_string(),
+            data: None,
+        }),
+    };
+
+    match diff_stored(&db, &file_key, &from, query.to.as_deref(), query.node_id.as_deref()).await {
+        Ok(diff) => HttpResponse::Ok().json(ApiResponse {
+            message: format!(
+                "{} added, {} removed, {} moved, {} changed",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.moved.len(),
+                diff.changed.len()
+            ),
+            file_key,
+            status: "success".to_string(),
+            data: Some(diff),
+        }),
+        Err(e @ DiffError::Missing(_)) => HttpResponse::NotFound().json(ApiResponse {
+            file_key,
+            message: e.to_string(),
+            status: "error".to_string(),
+            data: None,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse {
+            file_key,
+            message: e.to_string(),
+            status: "error".to_string(),
+            data: None,
+        }),
+    }
+}
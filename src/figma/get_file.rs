@@ -1,8 +1,10 @@
 use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use reqwest::Client;
-use mongodb::Client as MongoClient;
+use crate::figma::client::{FigmaClient, RETRIES_HEADER};
+use crate::figma::cache::{self, CacheMode, CacheStatus, Snapshot, CACHE_HEADER};
+use crate::figma::models::{FigmaFile, StoredFile};
+use crate::figma::store;
 
 // Struct for query parameters
 #[derive(Deserialize)]
@@ -14,6 +16,7 @@ pub struct FileParams {
     geometry: Option<String>,      
     plugin_data: Option<String>,   
     branch_data: Option<String>,   
+    cache: Option<CacheMode>,
 }
 
 // Struct for returning response
@@ -22,7 +25,7 @@ pub struct ApiResponse {
     file_key: String,
     message: String,
     status: String,
-    data: Option<serde_json::Value>,
+    data: Option<FigmaFile>,
 }
 
 // GET handler that makes an actual request to Figma API
@@ -31,6 +34,7 @@ pub async fn get_file(
     req: HttpRequest,
     query: web::Query<FileParams>,
     db: web::Data<crate::db::mongo::MongoDb>,
+    client: web::Data<FigmaClient>,
 ) -> impl Responder {
     // Check for X-Figma-Token header
     let token = match req.headers().get("X-Figma-Token") {
@@ -62,12 +66,6 @@ pub async fn get_file(
         }),
     };
 
-    // Create HTTP client
-    let client = Client::new();
-    
-    // Build the Figma API URL (using HashMap approach for cleaner parameter building)
-    let url = format!("https://api.figma.com/v1/files/{}", file_key);
-    
     // Build query parameters using HashMap
     let mut params = HashMap::new();
     if let Some(version) = &query.version {
@@ -89,64 +87,132 @@ pub async fn get_file(
         params.insert("branch_data", branch_data.clone());
     }
 
-    // Make the API request to Figma
-    let response = match client
-        .get(url)
-        .query(&params)  // Use .query() with HashMap
-        .header("X-Figma-Token", &token)
-        .send()
-        .await {
-            Ok(resp) => resp,
-            Err(e) => return HttpResponse::InternalServerError().json(ApiResponse {
-                file_key: "".to_string(),
-                message: format!("Failed to call Figma API: {}", e),
-                status: "error".to_string(),
-                data: None,
-            }),
-        };
-
-    // Check status code before consuming response body
-    if !response.status().is_success() {
-        let status = response.status();
-        // Get error response body
-        let error_text = match response.text().await {
-            Ok(text) => text,
-            Err(_) => "Could not read error response".to_string(),
-        };
-        
-        return HttpResponse::build(status).json(ApiResponse {
-            file_key: "".to_string(),
-            message: format!("Figma API error: {}", error_text),
+    let mode = query.cache.unwrap_or_default();
+    let snapshot = cache::snapshot_for(&params);
+    let lookup = match mode.lookup(snapshot.as_ref()) {
+        Ok(lookup) => lookup,
+        Err(message) => return HttpResponse::BadRequest().json(ApiResponse {
+            file_key: file_key.clone(),
+            message: message.to_string(),
             status: "error".to_string(),
             data: None,
-        });
+        }),
+    };
+
+    match lookup {
+        Some(Snapshot::Version(version)) => {
+            match store::file_version(&db, &file_key, version).await {
+                Ok(Some(stored)) => return cached_response(stored, CacheStatus::Hit, 0),
+                Ok(None) if mode == CacheMode::Only => {
+                    return HttpResponse::NotFound().json(ApiResponse {
+                        file_key: file_key.clone(),
+                        message: format!("No cached copy of version {} of Figma file: {}", version, file_key),
+                        status: "error".to_string(),
+                        data: None,
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to read cached Figma file version: {}", e),
+            }
+        }
+        Some(Snapshot::Current) => {
+            let cached = match store::current_file(&db, &file_key).await {
+                Ok(cached) => cached,
+                Err(e) => {
+                    eprintln!("Failed to read cached Figma file: {}", e);
+                    None
+                }
+            };
+
+            match cached {
+                None if mode == CacheMode::Only => {
+                    return HttpResponse::NotFound().json(ApiResponse {
+                        file_key: file_key.clone(),
+                        message: format!("No cached copy of Figma file: {}", file_key),
+                        status: "error".to_string(),
+                        data: None,
+                    });
+                }
+                Some(stored) if mode == CacheMode::Only || cache::within_ttl(client.cache_ttl(), &stored) => {
+                    return cached_response(stored, CacheStatus::Hit, 0);
+                }
+                Some(stored) => {
+                    // Stale copy: compare versions with a cheap metadata call
+                    if let Some(data) = &stored.data {
+                        match cache::is_unchanged(&client, &token, &file_key, data).await {
+                            Ok((true, retries)) => {
+                                if let Err(e) = cache::touch(&db, &file_key).await {
+                                    eprintln!("Failed to refresh cached Figma file: {}", e);
+                                }
+                                return cached_response(stored, CacheStatus::Revalidated, retries);
+                            }
+                            Ok((false, _)) => {}
+                            Err(e) => eprintln!("Failed to revalidate cached Figma file: {}", e),
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+        None => {}
     }
+    let cache_status = mode.download_status(snapshot.as_ref());
 
-    // Parse JSON response directly (more efficient - single step)
-    match response.json::<serde_json::Value>().await {
-        Ok(json_data) => {
+    // Make the API request to Figma and parse it into the typed file model
+    match client.get_file(&token, &file_key, &params).await {
+        Ok(fetched) => {
             // Create the API response
             let api_response = ApiResponse {
                 file_key: file_key.clone(),
-                message: format!("Successfully retrieved Figma file: {}", file_key),
+                message: fetched.describe(format!("Successfully retrieved Figma file: {}", file_key)),
                 status: "success".to_string(),
-                data: Some(json_data.clone()),
+                data: Some(fetched.data),
             };
 
-            // Store the response in MongoDB
-            if let Err(e) = db.insert_into_collection("figma_file", &api_response).await {
+            // Store the response in MongoDB as a snapshot: the latest file
+            // becomes current, pinned versions are kept as history.
+            // Partial requests are not stored.
+            if let Some(snapshot) = &snapshot
+                && let Some(data) = &api_response.data
+                && let Err(e) = store::save_file_snapshot(
+                    &db,
+                    &file_key,
+                    &api_response.message,
+                    data,
+                    *snapshot == Snapshot::Current,
+                ).await
+            {
                 eprintln!("Failed to store response in MongoDB: {}", e);
             }
 
-            HttpResponse::Ok().json(api_response)
+            HttpResponse::Ok()
+                .insert_header((RETRIES_HEADER, fetched.retries.to_string()))
+                .insert_header((CACHE_HEADER, cache_status.as_str()))
+                .json(api_response)
         },
         Err(e) => {
-            HttpResponse::InternalServerError().json(ApiResponse {
-                file_key: "".to_string(),
-                message: format!("Failed to parse Figma API response: {}", e),
-                status: "error".to_string(),
-                data: None,
-            })
+            HttpResponse::build(e.status_code())
+                .insert_header((RETRIES_HEADER, e.retries().to_string()))
+                .insert_header((CACHE_HEADER, cache_status.as_str()))
+                .json(ApiResponse {
+                    file_key: "".to_string(),
+                    message: e.to_string(),
+                    status: "error".to_string(),
+                    data: None,
+                })
         }
     }
-}
\ No newline at end of file
+}
+
+// Answer from a stored copy
+fn cached_response(stored: StoredFile, cache_status: CacheStatus, retries: u32) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((RETRIES_HEADER, retries.to_string()))
+        .insert_header((CACHE_HEADER, cache_status.as_str()))
+        .json(ApiResponse {
+            message: format!("Served Figma file {} from cache", stored.file_key),
+            file_key: stored.file_key,
+            status: "success".to_string(),
+            data: stored.data,
+        })
+}
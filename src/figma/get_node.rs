@@ -1,8 +1,8 @@
 use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use reqwest::Client;
-use mongodb::Client as MongoClient;
+use crate::figma::client::{FigmaClient, RETRIES_HEADER};
+use crate::figma::models::FigmaFileNodes;
+use crate::figma::store::{self, normalize_node_id};
 
 // Struct for query parameters
 #[derive(Deserialize)]
@@ -18,7 +18,7 @@ pub struct ApiResponse {
     node_id: String,
     message: String,
     status: String,
-    data: Option<serde_json::Value>,
+    data: Option<FigmaFileNodes>,
 }
 
 // GET handler that makes an actual request to Figma API
@@ -27,6 +27,7 @@ pub async fn get_node(
     req: HttpRequest,
     query: web::Query<NodeParams>,
     db: web::Data<crate::db::mongo::MongoDb>,
+    client: web::Data<FigmaClient>,
 ) -> impl Responder {
     // Check for X-Figma-Token header
     let token = match req.headers().get("X-Figma-Token") {
@@ -73,78 +74,39 @@ pub async fn get_node(
         }),
     };
 
-    // Create HTTP client
-    let client = Client::new();
-    
-    // Build the Figma API URL for nodes
-    let url = format!("https://api.figma.com/v1/files/{}/nodes", file_key);
-    
-    // Build query parameters using HashMap
-    let mut params = HashMap::new();
-    params.insert("ids", ids.clone());
-
-    // Make the API request to Figma
-    let response = match client
-        .get(url)
-        .query(&params)
-        .header("X-Figma-Token", &token)
-        .send()
-        .await {
-            Ok(resp) => resp,
-            Err(e) => return HttpResponse::InternalServerError().json(ApiResponse {
-                file_key: "".to_string(),
-                node_id: ids.clone(),
-                message: format!("Failed to call Figma API: {}", e),
-                status: "error".to_string(),
-                data: None,
-            }),
-        };
-
-    // Check status code before consuming response body
-    if !response.status().is_success() {
-        let status = response.status();
-        // Get error response body
-        let error_text = match response.text().await {
-            Ok(text) => text,
-            Err(_) => "Could not read error response".to_string(),
-        };
-        
-        return HttpResponse::build(status).json(ApiResponse {
-            file_key: "".to_string(),
-            node_id: ids.clone(),
-            message: format!("Figma API error: {}", error_text),
-            status: "error".to_string(),
-            data: None,
-        });
-    }
-
-    // Parse JSON response directly
-    match response.json::<serde_json::Value>().await {
-        Ok(json_data) => {
+    // Make the API request to Figma and parse it into the typed nodes model
+    match client.get_nodes(&token, &file_key, &ids).await {
+        Ok(fetched) => {
             // Create the API response
             let api_response = ApiResponse {
                 file_key: file_key.clone(),
                 node_id: ids.clone(),
-                message: format!("Successfully retrieved Figma nodes for file: {}", file_key),
+                message: fetched.describe(format!("Successfully retrieved Figma nodes for file: {}", file_key)),
                 status: "success".to_string(),
-                data: Some(json_data.clone()),
+                data: Some(fetched.data),
             };
 
             // Store the response in MongoDB
-            if let Err(e) = db.insert_into_collection("figma_nodes", &api_response).await {
+            if let Some(data) = &api_response.data
+                && let Err(e) = store::save_nodes_snapshot(&db, &file_key, &normalize_node_id(&ids), &api_response.message, data).await
+            {
                 eprintln!("Failed to store response in MongoDB: {}", e);
             }
 
-            HttpResponse::Ok().json(api_response)
+            HttpResponse::Ok()
+                .insert_header((RETRIES_HEADER, fetched.retries.to_string()))
+                .json(api_response)
         },
         Err(e) => {
-            HttpResponse::InternalServerError().json(ApiResponse {
-                file_key: "".to_string(),
-                node_id: ids.clone(),
-                message: format!("Failed to parse Figma API response: {}", e),
-                status: "error".to_string(),
-                data: None,
-            })
+            HttpResponse::build(e.status_code())
+                .insert_header((RETRIES_HEADER, e.retries().to_string()))
+                .json(ApiResponse {
+                    file_key: "".to_string(),
+                    node_id: ids.clone(),
+                    message: e.to_string(),
+                    status: "error".to_string(),
+                    data: None,
+                })
         }
     }
-} 
\ No newline at end of file
+}
@@ -1,5 +1,17 @@
 // Export all modules to make them accessible
 pub mod routes;
+#[allow(dead_code)]
 pub mod echo;
 pub mod get_file;
-pub mod get_node;
\ No newline at end of file
+pub mod get_node;
+pub mod store;
+pub mod models;
+pub mod client;
+pub mod config;
+pub mod rate_limit;
+pub mod cache;
+pub mod versions;
+pub mod diff;
+pub mod get_diff;
+pub mod webhook;
+pub mod variables;
\ No newline at end of file
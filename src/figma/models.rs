@@ -0,0 +1,1022 @@
+// Serde model of the Figma REST file format.
+//
+// Documents, nodes, paints, styles and the other open-ended Figma objects keep
+// fields they do not know about in `extra`, and every enum keeps unrecognised
+// values in `Other`, so newer Figma payloads still parse and round-trip
+// through Mongo unchanged. The small value types (Rectangle, Vector, Color,
+// LayoutConstraint, ColorStop, VariableMode, VariableAlias and Pagination)
+// only carry the fields declared here and drop anything else.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+// Declares a string enum that serializes as Figma's SCREAMING_CASE value and
+// falls back to `Other` for values added after this model was written
+macro_rules! string_enum {
+    ($name:ident { $($variant:ident => $value:literal),* $(,)? }) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($variant,)*
+            Other(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($value => $name::$variant,)*
+                    _ => $name::Other(value),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.as_str().to_string()
+            }
+        }
+    };
+}
+
+string_enum!(NodeType {
+    Document => "DOCUMENT",
+    Canvas => "CANVAS",
+    Frame => "FRAME",
+    Group => "GROUP",
+    Section => "SECTION",
+    Vector => "VECTOR",
+    BooleanOperation => "BOOLEAN_OPERATION",
+    Star => "STAR",
+    Line => "LINE",
+    Ellipse => "ELLIPSE",
+    RegularPolygon => "REGULAR_POLYGON",
+    Rectangle => "RECTANGLE",
+    Table => "TABLE",
+    TableCell => "TABLE_CELL",
+    Text => "TEXT",
+    Slice => "SLICE",
+    Component => "COMPONENT",
+    ComponentSet => "COMPONENT_SET",
+    Instance => "INSTANCE",
+    Sticky => "STICKY",
+    ShapeWithText => "SHAPE_WITH_TEXT",
+    Connector => "CONNECTOR",
+    WashiTape => "WASHI_TAPE",
+});
+
+string_enum!(PaintType {
+    Solid => "SOLID",
+    GradientLinear => "GRADIENT_LINEAR",
+    GradientRadial => "GRADIENT_RADIAL",
+    GradientAngular => "GRADIENT_ANGULAR",
+    GradientDiamond => "GRADIENT_DIAMOND",
+    Image => "IMAGE",
+    Emoji => "EMOJI",
+    Video => "VIDEO",
+});
+
+string_enum!(EffectType {
+    DropShadow => "DROP_SHADOW",
+    InnerShadow => "INNER_SHADOW",
+    LayerBlur => "LAYER_BLUR",
+    BackgroundBlur => "BACKGROUND_BLUR",
+});
+
+string_enum!(LayoutMode {
+    None => "NONE",
+    Horizontal => "HORIZONTAL",
+    Vertical => "VERTICAL",
+});
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_one() -> f64 {
+    1.0
+}
+
+// Response of GET /v1/files/:key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FigmaFile {
+    #[serde(default)]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub document: Node,
+    #[serde(default)]
+    pub components: HashMap<String, ComponentMeta>,
+    #[serde(default)]
+    pub component_sets: HashMap<String, ComponentSetMeta>,
+    #[serde(default)]
+    pub styles: HashMap<String, StyleMeta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// Response of GET /v1/files/:key/nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FigmaFileNodes {
+    #[serde(default)]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    // Ids that do not exist in the file come back as null
+    #[serde(default)]
+    pub nodes: HashMap<String, Option<NodeEntry>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeEntry {
+    pub document: Node,
+    #[serde(default)]
+    pub components: HashMap<String, ComponentMeta>,
+    #[serde(default)]
+    pub component_sets: HashMap<String, ComponentSetMeta>,
+    #[serde(default)]
+    pub styles: HashMap<String, StyleMeta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// A single node of the document tree. Figma node types share most of their
+// properties, so one struct with optional fields covers all of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: NodeType,
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Node>,
+
+    // Geometry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_bounding_box: Option<Rectangle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_render_bounds: Option<Rectangle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<LayoutConstraint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clips_content: Option<bool>,
+
+    // Appearance
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fills: Vec<Paint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strokes: Vec<Paint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stroke_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stroke_align: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corner_radius: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rectangle_corner_radii: Option<Vec<f64>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<Effect>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blend_mode: Option<String>,
+
+    // Auto-layout container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_mode: Option<LayoutMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_wrap: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_axis_sizing_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counter_axis_sizing_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_axis_align_items: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counter_axis_align_items: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counter_axis_align_content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_left: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_right: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_top: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_bottom: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_spacing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub counter_axis_spacing: Option<f64>,
+
+    // Auto-layout child
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_align: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_grow: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_positioning: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_sizing_horizontal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_sizing_vertical: Option<String>,
+
+    // Text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub characters: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<TypeStyle>,
+
+    // References to shared styles, components and variables
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub styles: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bound_variables: Option<Value>,
+
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Node {
+    // Depth-first search for a node id in this subtree
+    pub fn find(&self, node_id: &str) -> Option<&Node> {
+        if self.id == node_id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(node_id))
+    }
+
+    pub fn is_auto_layout(&self) -> bool {
+        matches!(self.layout_mode, Some(LayoutMode::Horizontal) | Some(LayoutMode::Vertical))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Rectangle {
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default)]
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Vector {
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Color {
+    #[serde(default)]
+    pub r: f64,
+    #[serde(default)]
+    pub g: f64,
+    #[serde(default)]
+    pub b: f64,
+    #[serde(default = "default_one")]
+    pub a: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutConstraint {
+    pub vertical: String,
+    pub horizontal: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Paint {
+    #[serde(rename = "type")]
+    pub paint_type: PaintType,
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    #[serde(default = "default_one")]
+    pub opacity: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blend_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gradient_handle_positions: Vec<Vector>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gradient_stops: Vec<ColorStop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bound_variables: Option<Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorStop {
+    pub position: f64,
+    pub color: Color,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bound_variables: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Effect {
+    #[serde(rename = "type")]
+    pub effect_type: EffectType,
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    #[serde(default)]
+    pub radius: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Vector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spread: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blend_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_shadow_behind_node: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bound_variables: Option<Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeStyle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_post_script_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_align_horizontal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_align_vertical: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub letter_spacing: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height_px: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height_percent_font_size: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_height_unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_case: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_decoration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_auto_resize: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentMeta {
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_set_id: Option<String>,
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentSetMeta {
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleMeta {
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // FILL, TEXT, EFFECT or GRID
+    #[serde(default)]
+    pub style_type: String,
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// Response of GET /v1/files/:key/versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVersions {
+    #[serde(default)]
+    pub versions: Vec<FileVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVersion {
+    pub id: String,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<FigmaUser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FigmaUser {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub handle: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub img_url: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pagination {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_page: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page: Option<String>,
+}
+
+string_enum!(VariableType {
+    Boolean => "BOOLEAN",
+    Float => "FLOAT",
+    String => "STRING",
+    Color => "COLOR",
+});
+
+// `meta` envelope shared by the variables endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariablesResponse<T> {
+    #[serde(default)]
+    pub error: bool,
+    pub meta: T,
+}
+
+// Response of GET /v1/files/:key/variables/local
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalVariables {
+    #[serde(default)]
+    pub variables: HashMap<String, Variable>,
+    #[serde(default)]
+    pub variable_collections: HashMap<String, VariableCollection>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub key: String,
+    pub variable_collection_id: String,
+    pub resolved_type: VariableType,
+    // Keyed by mode id
+    #[serde(default)]
+    pub values_by_mode: HashMap<String, VariableValue>,
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub hidden_from_publishing: bool,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub code_syntax: HashMap<String, String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableCollection {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub modes: Vec<VariableMode>,
+    #[serde(default)]
+    pub default_mode_id: String,
+    #[serde(default)]
+    pub remote: bool,
+    #[serde(default)]
+    pub hidden_from_publishing: bool,
+    #[serde(default)]
+    pub variable_ids: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VariableMode {
+    pub mode_id: String,
+    pub name: String,
+}
+
+// A variable's value in one mode. Aliases are listed first because Color
+// has only defaulted fields and would match any object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VariableValue {
+    Alias(VariableAlias),
+    Color(Color),
+    Boolean(bool),
+    Float(f64),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariableAlias {
+    // Always "VARIABLE_ALIAS"
+    #[serde(rename = "type")]
+    pub alias_type: String,
+    pub id: String,
+}
+
+// A value reached by following aliases from one variable in one mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AliasChain {
+    pub variable_id: String,
+    pub mode_id: String,
+    // Variables passed through, in order, ending with the one holding the value
+    pub chain: Vec<String>,
+    // None when the chain is broken or loops
+    pub resolved: Option<VariableValue>,
+}
+
+impl LocalVariables {
+    // Follow aliases from a variable's value in a mode. When an alias points
+    // into another collection the mode with the same name is used there,
+    // falling back to that collection's default mode.
+    pub fn resolve(&self, variable_id: &str, mode_id: &str) -> AliasChain {
+        let mut chain = Vec::new();
+        let mut current = variable_id.to_string();
+        let mut mode = mode_id.to_string();
+
+        let resolved = loop {
+            let Some(variable) = self.variables.get(&current) else {
+                break None;
+            };
+            let Some(value) = variable
+                .values_by_mode
+                .get(&mode)
+                .or_else(|| self.default_value(variable))
+            else {
+                break None;
+            };
+            let VariableValue::Alias(alias) = value else {
+                break Some(value.clone());
+            };
+            if alias.id == variable_id || chain.contains(&alias.id) {
+                break None;
+            }
+
+            mode = self.matching_mode(&variable.variable_collection_id, &mode, &alias.id);
+            chain.push(alias.id.clone());
+            current = alias.id.clone();
+        };
+
+        AliasChain {
+            variable_id: variable_id.to_string(),
+            mode_id: mode_id.to_string(),
+            chain,
+            resolved,
+        }
+    }
+
+    // Alias chains of every aliased value, sorted for stable storage
+    pub fn alias_chains(&self) -> Vec<AliasChain> {
+        let mut chains: Vec<AliasChain> = self
+            .variables
+            .values()
+            .flat_map(|variable| {
+                variable
+                    .values_by_mode
+                    .iter()
+                    .filter(|(_, value)| matches!(value, VariableValue::Alias(_)))
+                    .map(|(mode_id, _)| self.resolve(&variable.id, mode_id))
+            })
+            .collect();
+        chains.sort_by(|a, b| (&a.variable_id, &a.mode_id).cmp(&(&b.variable_id, &b.mode_id)));
+        chains
+    }
+
+    fn default_value<'a>(&self, variable: &'a Variable) -> Option<&'a VariableValue> {
+        let collection = self.variable_collections.get(&variable.variable_collection_id)?;
+        variable.values_by_mode.get(&collection.default_mode_id)
+    }
+
+    // Mode of the target variable's collection to read after an alias
+    fn matching_mode(&self, from_collection: &str, mode_id: &str, target_id: &str) -> String {
+        let Some(target) = self
+            .variables
+            .get(target_id)
+            .and_then(|v| self.variable_collections.get(&v.variable_collection_id))
+        else {
+            return mode_id.to_string();
+        };
+        if target.id == from_collection {
+            return mode_id.to_string();
+        }
+        let mode_name = self
+            .variable_collections
+            .get(from_collection)
+            .and_then(|c| c.modes.iter().find(|m| m.mode_id == mode_id))
+            .map(|m| m.name.as_str());
+        target
+            .modes
+            .iter()
+            .find(|m| Some(m.name.as_str()) == mode_name)
+            .map(|m| m.mode_id.clone())
+            .unwrap_or_else(|| target.default_mode_id.clone())
+    }
+}
+
+// Response of GET /v1/files/:key/variables/published
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedVariables {
+    #[serde(default)]
+    pub variables: HashMap<String, PublishedVariable>,
+    #[serde(default)]
+    pub variable_collections: HashMap<String, PublishedVariableCollection>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedVariable {
+    pub id: String,
+    #[serde(rename = "subscribed_id", default)]
+    pub subscribed_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub key: String,
+    pub variable_collection_id: String,
+    pub resolved_data_type: VariableType,
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedVariableCollection {
+    pub id: String,
+    #[serde(rename = "subscribed_id", default)]
+    pub subscribed_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub updated_at: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// Documents stored in the figma_file collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFile {
+    pub file_key: String,
+    pub message: String,
+    pub status: String,
+    pub data: Option<FigmaFile>,
+    // When the copy was last fetched or confirmed fresh against Figma
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub fetched_at: Option<DateTime<Utc>>,
+    // Figma version of `data`; one snapshot is kept per version
+    #[serde(default)]
+    pub version: Option<String>,
+    // sha256 of `data`, used to skip storing identical payloads
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    // The latest snapshot of this file; older ones are history
+    #[serde(default)]
+    pub current: bool,
+}
+
+// Documents stored in the figma_nodes collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredNodes {
+    pub file_key: String,
+    pub node_id: String,
+    pub message: String,
+    pub status: String,
+    pub data: Option<FigmaFileNodes>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub fetched_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub current: bool,
+}
+
+// Documents stored in the figma_versions collection, one per file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredVersions {
+    pub file_key: String,
+    // Newest first, merged across every page fetched so far
+    pub versions: Vec<FileVersion>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+// Documents stored in the figma_variables collection. Local and published
+// variables form separate histories, tagged with the file version that was
+// current when they were fetched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredVariables {
+    pub file_key: String,
+    // "local" or "published"
+    pub scope: String,
+    pub message: String,
+    pub status: String,
+    #[serde(default)]
+    pub local: Option<LocalVariables>,
+    #[serde(default)]
+    pub published: Option<PublishedVariables>,
+    // Resolved aliases of the local variables
+    #[serde(default)]
+    pub alias_chains: Vec<AliasChain>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub fetched_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub current: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn alias(id: &str) -> Value {
+        json!({ "type": "VARIABLE_ALIAS", "id": id })
+    }
+
+    // Primitives have light and dark modes; semantic colors alias them from
+    // modes of the same names, and the spacing collection has a single mode
+    fn variables() -> LocalVariables {
+        serde_json::from_value(json!({
+            "variableCollections": {
+                "c:primitives": {
+                    "id": "c:primitives",
+                    "name": "Primitives",
+                    "modes": [{ "modeId": "p:light", "name": "Light" }, { "modeId": "p:dark", "name": "Dark" }],
+                    "defaultModeId": "p:light",
+                },
+                "c:semantic": {
+                    "id": "c:semantic",
+                    "name": "Semantic",
+                    "modes": [{ "modeId": "s:light", "name": "Light" }, { "modeId": "s:dark", "name": "Dark" }],
+                    "defaultModeId": "s:light",
+                },
+                "c:spacing": {
+                    "id": "c:spacing",
+                    "name": "Spacing",
+                    "modes": [{ "modeId": "sp:base", "name": "Base" }],
+                    "defaultModeId": "sp:base",
+                },
+            },
+            "variables": {
+                "v:blue": {
+                    "id": "v:blue",
+                    "name": "blue",
+                    "variableCollectionId": "c:primitives",
+                    "resolvedType": "COLOR",
+                    "valuesByMode": {
+                        "p:light": { "r": 0.0, "g": 0.0, "b": 1.0, "a": 1.0 },
+                        "p:dark": { "r": 0.0, "g": 0.0, "b": 0.5, "a": 1.0 },
+                    },
+                },
+                "v:accent": {
+                    "id": "v:accent",
+                    "name": "accent",
+                    "variableCollectionId": "c:primitives",
+                    "resolvedType": "COLOR",
+                    "valuesByMode": { "p:light": alias("v:blue"), "p:dark": alias("v:blue") },
+                },
+                "v:primary": {
+                    "id": "v:primary",
+                    "name": "primary",
+                    "variableCollectionId": "c:semantic",
+                    "resolvedType": "COLOR",
+                    "valuesByMode": { "s:light": alias("v:accent"), "s:dark": alias("v:accent") },
+                },
+                "v:gap": {
+                    "id": "v:gap",
+                    "name": "gap",
+                    "variableCollectionId": "c:spacing",
+                    "resolvedType": "FLOAT",
+                    "valuesByMode": { "sp:base": 8.0 },
+                },
+                "v:padding": {
+                    "id": "v:padding",
+                    "name": "padding",
+                    "variableCollectionId": "c:semantic",
+                    "resolvedType": "FLOAT",
+                    "valuesByMode": { "s:dark": alias("v:gap"), "s:light": alias("v:gap") },
+                },
+                "v:ping": {
+                    "id": "v:ping",
+                    "name": "ping",
+                    "variableCollectionId": "c:semantic",
+                    "resolvedType": "FLOAT",
+                    "valuesByMode": { "s:light": alias("v:pong") },
+                },
+                "v:pong": {
+                    "id": "v:pong",
+                    "name": "pong",
+                    "variableCollectionId": "c:semantic",
+                    "resolvedType": "FLOAT",
+                    "valuesByMode": { "s:light": alias("v:ping") },
+                },
+                "v:broken": {
+                    "id": "v:broken",
+                    "name": "broken",
+                    "variableCollectionId": "c:semantic",
+                    "resolvedType": "FLOAT",
+                    "valuesByMode": { "s:light": alias("v:deleted") },
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn color(r: f64, g: f64, b: f64) -> Option<VariableValue> {
+        Some(VariableValue::Color(Color { r, g, b, a: 1.0 }))
+    }
+
+    #[test]
+    fn values_deserialize_as_aliases_before_colors() {
+        let variables = variables();
+        let primary = &variables.variables["v:primary"].values_by_mode["s:light"];
+        assert!(matches!(primary, VariableValue::Alias(alias) if alias.id == "v:accent"));
+        assert_eq!(variables.variables["v:gap"].values_by_mode["sp:base"], VariableValue::Float(8.0));
+    }
+
+    #[test]
+    fn alias_chains_follow_the_mode_with_the_same_name() {
+        let variables = variables();
+        let dark = variables.resolve("v:primary", "s:dark");
+        assert_eq!(dark.chain, ["v:accent", "v:blue"]);
+        assert_eq!(dark.resolved, color(0.0, 0.0, 0.5));
+        assert_eq!(variables.resolve("v:primary", "s:light").resolved, color(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn collections_without_the_mode_fall_back_to_their_default() {
+        let chain = variables().resolve("v:padding", "s:dark");
+        assert_eq!(chain.chain, ["v:gap"]);
+        assert_eq!(chain.resolved, Some(VariableValue::Float(8.0)));
+    }
+
+    #[test]
+    fn plain_values_resolve_to_themselves() {
+        let chain = variables().resolve("v:gap", "sp:base");
+        assert!(chain.chain.is_empty());
+        assert_eq!(chain.resolved, Some(VariableValue::Float(8.0)));
+    }
+
+    #[test]
+    fn cycles_and_broken_aliases_do_not_resolve() {
+        let variables = variables();
+        let cycle = variables.resolve("v:ping", "s:light");
+        assert_eq!(cycle.chain, ["v:pong"]);
+        assert_eq!(cycle.resolved, None);
+
+        let broken = variables.resolve("v:broken", "s:light");
+        assert_eq!(broken.chain, ["v:deleted"]);
+        assert_eq!(broken.resolved, None);
+        assert_eq!(variables.resolve("v:missing", "s:light").resolved, None);
+    }
+
+    #[test]
+    fn every_aliased_value_gets_a_sorted_chain() {
+        let chains: Vec<(String, String)> = variables()
+            .alias_chains()
+            .into_iter()
+            .map(|chain| (chain.variable_id, chain.mode_id))
+            .collect();
+        let expected = [
+            ("v:accent", "p:dark"),
+            ("v:accent", "p:light"),
+            ("v:broken", "s:light"),
+            ("v:padding", "s:dark"),
+            ("v:padding", "s:light"),
+            ("v:ping", "s:light"),
+            ("v:pong", "s:light"),
+            ("v:primary", "s:dark"),
+            ("v:primary", "s:light"),
+        ];
+        assert_eq!(chains, expected.map(|(id, mode)| (id.to_string(), mode.to_string())));
+    }
+}
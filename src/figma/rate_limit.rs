@@ -0,0 +1,166 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+// Per-access-token token bucket, so a burst from one user cannot get their
+// personal access token throttled by Figma
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_second: f64,
+    buckets: Mutex<Buckets>,
+}
+
+struct Buckets {
+    by_token: HashMap<String, TokenBucket>,
+    last_sweep: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(capacity: f64, refill_per_second: f64) -> Self {
+        RateLimiter {
+            capacity: capacity.max(1.0),
+            refill_per_second: refill_per_second.max(0.01),
+            buckets: Mutex::new(Buckets { by_token: HashMap::new(), last_sweep: Instant::now() }),
+        }
+    }
+
+    // Wait until a request may be sent for this token
+    pub async fn acquire(&self, token: &str) {
+        let key = bucket_key(token);
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                self.sweep(&mut buckets);
+                let bucket = buckets.by_token.entry(key.clone()).or_insert_with(|| TokenBucket {
+                    tokens: self.capacity,
+                    last_refill: Instant::now(),
+                });
+                self.refill(bucket);
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.refill_per_second)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Figma told us to slow down: empty the bucket so queued requests back off too
+    pub fn drain(&self, token: &str) {
+        let mut buckets = self.buckets.lock().unwrap();
+        if let Some(bucket) = buckets.by_token.get_mut(&bucket_key(token)) {
+            self.refill(bucket);
+            bucket.tokens = 0.0;
+        }
+    }
+
+    // A bucket idle for longer than a full refill is back at capacity, the
+    // same as a new one, so it can be dropped. Runs at most once per period.
+    fn sweep(&self, buckets: &mut Buckets) {
+        let idle = self.full_refill();
+        if buckets.last_sweep.elapsed() < idle {
+            return;
+        }
+        buckets.by_token.retain(|_, bucket| bucket.last_refill.elapsed() < idle);
+        buckets.last_sweep = Instant::now();
+    }
+
+    fn full_refill(&self) -> Duration {
+        Duration::from_secs_f64(self.capacity / self.refill_per_second)
+    }
+
+    fn refill(&self, bucket: &mut TokenBucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        bucket.last_refill = now;
+    }
+}
+
+// Buckets are keyed by a hash so raw access tokens are not kept in memory
+fn bucket_key(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+// Exponential backoff with jitter: half the delay is fixed, half is random
+pub fn backoff_delay(attempt: u32, base: Duration, max: Duration) -> Duration {
+    let exponential = base.saturating_mul(2u32.saturating_pow(attempt)).min(max);
+    let half = exponential / 2;
+    half + half.mul_f64(fastrand::f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum_with_jitter_in_the_upper_half() {
+        let base = Duration::from_millis(100);
+        let max = Duration::from_secs(2);
+        for (attempt, expected) in [(0, 100), (1, 200), (3, 800), (5, 2000), (40, 2000)] {
+            let expected = Duration::from_millis(expected);
+            for _ in 0..50 {
+                let delay = backoff_delay(attempt, base, max);
+                assert!(delay >= expected / 2 && delay <= expected, "{:?} for attempt {}", delay, attempt);
+            }
+        }
+    }
+
+    #[test]
+    fn backoff_jitter_varies() {
+        let delays: std::collections::HashSet<Duration> = (0..20)
+            .map(|_| backoff_delay(4, Duration::from_millis(100), Duration::from_secs(10)))
+            .collect();
+        assert!(delays.len() > 1);
+    }
+
+    #[tokio::test]
+    async fn buckets_allow_a_burst_then_wait_for_a_refill() {
+        let limiter = RateLimiter::new(3.0, 10.0);
+        let started = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire("token").await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(50));
+        limiter.acquire("token").await;
+        assert!(started.elapsed() >= Duration::from_millis(90));
+        // Another token has a bucket of its own
+        let other = Instant::now();
+        limiter.acquire("other").await;
+        assert!(other.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn drained_buckets_make_the_next_request_wait() {
+        let limiter = RateLimiter::new(5.0, 10.0);
+        limiter.acquire("token").await;
+        limiter.drain("token");
+        let started = Instant::now();
+        limiter.acquire("token").await;
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn idle_buckets_are_swept_without_raw_tokens_as_keys() {
+        // A full refill takes 20ms
+        let limiter = RateLimiter::new(1.0, 50.0);
+        limiter.acquire("first").await;
+        {
+            let buckets = limiter.buckets.lock().unwrap();
+            assert_eq!(buckets.by_token.keys().collect::<Vec<_>>(), [&bucket_key("first")]);
+            assert!(!buckets.by_token.contains_key("first"));
+        }
+        std::thread::sleep(Duration::from_millis(30));
+        limiter.acquire("second").await;
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.by_token.keys().collect::<Vec<_>>(), [&bucket_key("second")]);
+    }
+}
@@ -1,8 +1,16 @@
 use actix_web::web;
 use crate::figma::get_file;
 use crate::figma::get_node;
+use crate::figma::versions;
+use crate::figma::get_diff;
+use crate::figma::webhook;
+use crate::figma::variables;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(get_file::get_file)
-    .service(get_node::get_node);
+    .service(get_node::get_node)
+    .service(versions::get_versions)
+    .service(get_diff::get_diff)
+    .service(variables::get_variables)
+    .service(webhook::receive_webhook);
 }
\ No newline at end of file
@@ -0,0 +1,374 @@
+use chrono::Utc;
+use mongodb::bson::doc;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use crate::db::mongo::{MongoDb, SnapshotOutcome};
+use crate::figma::models::{
+    FigmaFile, FigmaFileNodes, FileVersion, LocalVariables, Node, PublishedVariables, StoredFile,
+    StoredNodes, StoredVariables, StoredVersions,
+};
+
+// Figma URLs use "1-2" while the API uses "1:2"
+pub fn normalize_node_id(node_id: &str) -> String {
+    node_id.replace('-', ":")
+}
+
+// sha256 of the payload with object keys sorted, so the hash does not
+// depend on HashMap iteration order. serde_json keeps insertion order
+// here (bson enables its preserve_order feature), hence the explicit sort.
+pub fn content_hash<T: Serialize>(data: &T) -> Result<String, serde_json::Error> {
+    let canonical = serde_json::to_vec(&sorted_keys(serde_json::to_value(data)?))?;
+    Ok(hex::encode(Sha256::digest(&canonical)))
+}
+
+fn sorted_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sorted_keys).collect()),
+        other => other,
+    }
+}
+
+// A payload that cannot be hashed is not saved: an empty hash would make
+// unrelated snapshots look identical
+fn hash_payload<T: Serialize>(data: &T) -> Result<String, mongodb::error::Error> {
+    content_hash(data).map_err(|e| {
+        mongodb::error::Error::from(std::io::Error::other(format!(
+            "Failed to hash Figma payload: {}",
+            e
+        )))
+    })
+}
+
+// Unique keys of the snapshot histories, so concurrent saves of the same
+// version cannot insert it twice
+const SNAPSHOT_KEYS: [(&str, &[&str]); 3] = [
+    ("figma_file", &["file_key", "version"]),
+    ("figma_nodes", &["file_key", "node_id", "version"]),
+    ("figma_variables", &["file_key", "scope", "version"]),
+];
+
+// Older databases can hold several rows of one version, which would make
+// building the unique index fail. The current or most recently fetched row
+// of each version is kept.
+pub async fn create_indexes(db: &MongoDb) -> Result<(), mongodb::error::Error> {
+    for (collection, keys) in SNAPSHOT_KEYS {
+        let removed = db
+            .remove_duplicates(collection, keys, doc! { "current": -1, "fetched_at": -1 })
+            .await?;
+        if removed > 0 {
+            println!("Removed {} duplicate snapshots from {}", removed, collection);
+        }
+        let mut index = mongodb::bson::Document::new();
+        for key in keys {
+            index.insert(*key, 1);
+        }
+        db.create_unique_index(collection, index).await?;
+    }
+    Ok(())
+}
+
+// Save a downloaded file in its snapshot history. The latest download
+// becomes current; pinned historical versions are stored as history only.
+pub async fn save_file_snapshot(
+    db: &MongoDb,
+    file_key: &str,
+    message: &str,
+    data: &FigmaFile,
+    make_current: bool,
+) -> Result<SnapshotOutcome, mongodb::error::Error> {
+    let content_hash = hash_payload(data)?;
+    let stored = StoredFile {
+        file_key: file_key.to_string(),
+        message: message.to_string(),
+        status: "success".to_string(),
+        data: Some(data.clone()),
+        fetched_at: Some(Utc::now()),
+        version: data.version.clone(),
+        content_hash: Some(content_hash.clone()),
+        current: make_current,
+    };
+    db.upsert_snapshot(
+        "figma_file",
+        doc! { "file_key": file_key },
+        data.version.as_deref(),
+        &content_hash,
+        &stored,
+        make_current,
+    )
+    .await
+}
+
+// Save a getNode response as the current snapshot for these node ids
+pub async fn save_nodes_snapshot(
+    db: &MongoDb,
+    file_key: &str,
+    node_id: &str,
+    message: &str,
+    data: &FigmaFileNodes,
+) -> Result<SnapshotOutcome, mongodb::error::Error> {
+    let content_hash = hash_payload(data)?;
+    let stored = StoredNodes {
+        file_key: file_key.to_string(),
+        node_id: node_id.to_string(),
+        message: message.to_string(),
+        status: "success".to_string(),
+        data: Some(data.clone()),
+        fetched_at: Some(Utc::now()),
+        version: data.version.clone(),
+        content_hash: Some(content_hash.clone()),
+        current: true,
+    };
+    db.upsert_snapshot(
+        "figma_nodes",
+        doc! { "file_key": file_key, "node_id": node_id },
+        data.version.as_deref(),
+        &content_hash,
+        &stored,
+        true,
+    )
+    .await
+}
+
+// Current snapshot of a full file
+pub async fn current_file(
+    db: &MongoDb,
+    file_key: &str,
+) -> Result<Option<StoredFile>, mongodb::error::Error> {
+    let filter = doc! { "file_key": file_key, "current": true };
+    db.get_latest_document_from_collection("figma_file", filter, "fetched_at")
+        .await
+}
+
+// Snapshot of a specific Figma version, current or not
+pub async fn file_version(
+    db: &MongoDb,
+    file_key: &str,
+    version: &str,
+) -> Result<Option<StoredFile>, mongodb::error::Error> {
+    let filter = doc! { "file_key": file_key, "version": version };
+    db.get_document_from_collection("figma_file", filter).await
+}
+
+// Merge a page of version history into the stored list for a file
+pub async fn save_versions(
+    db: &MongoDb,
+    file_key: &str,
+    page: &[FileVersion],
+) -> Result<StoredVersions, mongodb::error::Error> {
+    let filter = doc! { "file_key": file_key };
+    let existing: Option<StoredVersions> = db
+        .get_document_from_collection("figma_versions", filter.clone())
+        .await?;
+
+    let mut versions = existing.map(|stored| stored.versions).unwrap_or_default();
+    for version in page {
+        match versions.iter_mut().find(|v| v.id == version.id) {
+            Some(known) => *known = version.clone(),
+            None => versions.push(version.clone()),
+        }
+    }
+    // created_at is ISO 8601, so string order is chronological
+    versions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let stored = StoredVersions {
+        file_key: file_key.to_string(),
+        versions,
+        fetched_at: Some(Utc::now()),
+    };
+    db.replace_in_collection("figma_versions", filter, &stored).await?;
+    Ok(stored)
+}
+
+// Save the file's local variables, with their alias chains resolved
+pub async fn save_local_variables(
+    db: &MongoDb,
+    file_key: &str,
+    message: &str,
+    data: &LocalVariables,
+) -> Result<SnapshotOutcome, mongodb::error::Error> {
+    let stored = StoredVariables {
+        file_key: file_key.to_string(),
+        scope: "local".to_string(),
+        message: message.to_string(),
+        status: "success".to_string(),
+        local: Some(data.clone()),
+        published: None,
+        alias_chains: data.alias_chains(),
+        fetched_at: Some(Utc::now()),
+        version: None,
+        content_hash: Some(hash_payload(data)?),
+        current: true,
+    };
+    save_variables(db, stored).await
+}
+
+pub async fn save_published_variables(
+    db: &MongoDb,
+    file_key: &str,
+    message: &str,
+    data: &PublishedVariables,
+) -> Result<SnapshotOutcome, mongodb::error::Error> {
+    let stored = StoredVariables {
+        file_key: file_key.to_string(),
+        scope: "published".to_string(),
+        message: message.to_string(),
+        status: "success".to_string(),
+        local: None,
+        published: Some(data.clone()),
+        alias_chains: Vec::new(),
+        fetched_at: Some(Utc::now()),
+        version: None,
+        content_hash: Some(hash_payload(data)?),
+        current: true,
+    };
+    save_variables(db, stored).await
+}
+
+// The variables endpoints carry no version, so snapshots are tagged with
+// the version of the current file snapshot
+async fn save_variables(
+    db: &MongoDb,
+    mut stored: StoredVariables,
+) -> Result<SnapshotOutcome, mongodb::error::Error> {
+    stored.version = current_file(db, &stored.file_key)
+        .await?
+        .and_then(|file| file.version);
+    let content_hash = stored.content_hash.clone().unwrap_or_default();
+    db.upsert_snapshot(
+        "figma_variables",
+        doc! { "file_key": &stored.file_key, "scope": &stored.scope },
+        stored.version.as_deref(),
+        &content_hash,
+        &stored,
+        true,
+    )
+    .await
+}
+
+// Current variables snapshot of a file for "local" or "published"
+pub async fn current_variables(
+    db: &MongoDb,
+    file_key: &str,
+    scope: &str,
+) -> Result<Option<StoredVariables>, mongodb::error::Error> {
+    let filter = doc! { "file_key": file_key, "scope": scope, "current": true };
+    db.get_latest_document_from_collection("figma_variables", filter, "fetched_at")
+        .await
+}
+
+// Look up a node previously stored by getNode / getFile.
+pub async fn find_node(
+    db: &MongoDb,
+    file_key: &str,
+    node_id: &str,
+) -> Result<Option<Node>, mongodb::error::Error> {
+    snapshot_node(db, file_key, None, Some(node_id)).await
+}
+
+// Root of a stored snapshot: the file's document, or one node inside it.
+// `version` None means the current snapshot. Nodes are looked up in the
+// figma_nodes collection first, then in the full file tree.
+pub async fn snapshot_node(
+    db: &MongoDb,
+    file_key: &str,
+    version: Option<&str>,
+    node_id: Option<&str>,
+) -> Result<Option<Node>, mongodb::error::Error> {
+    let node_id = node_id.map(normalize_node_id);
+
+    if let Some(node_id) = &node_id {
+        // A getNode response may hold several ids, so match on the nodes map itself
+        let mut filter = doc! {
+            "file_key": file_key,
+            format!("data.nodes.{}", node_id): { "$exists": true },
+        };
+        match version {
+            Some(version) => filter.insert("version", version),
+            None => filter.insert("current", true),
+        };
+        let stored: Option<StoredNodes> = db
+            .get_latest_document_from_collection("figma_nodes", filter, "fetched_at")
+            .await?;
+        if let Some(entry) = stored
+            .and_then(|doc| doc.data)
+            .and_then(|mut data| data.nodes.remove(node_id).flatten())
+        {
+            return Ok(Some(entry.document));
+        }
+    }
+
+    let stored = match version {
+        Some(version) => file_version(db, file_key, version).await?,
+        None => current_file(db, file_key).await?,
+    };
+    let Some(document) = stored.and_then(|doc| doc.data).map(|data| data.document) else {
+        return Ok(None);
+    };
+    Ok(match &node_id {
+        Some(node_id) => document.find(node_id).cloned(),
+        None => Some(document),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn content_hash_ignores_map_order() {
+        let keys: Vec<String> = (0..32).map(|i| format!("key-{}", i)).collect();
+        let mut forward = HashMap::new();
+        for key in &keys {
+            forward.insert(key.clone(), HashMap::from([("inner-a", 1), ("inner-b", 2)]));
+        }
+        let mut backward = HashMap::new();
+        for key in keys.iter().rev() {
+            backward.insert(key.clone(), HashMap::from([("inner-b", 2), ("inner-a", 1)]));
+        }
+        assert_eq!(content_hash(&forward).unwrap(), content_hash(&backward).unwrap());
+
+        // serde_json keeps insertion order, so this differs before sorting
+        let a = serde_json::json!({ "x": 1, "y": { "p": 1, "q": 2 } });
+        let b = serde_json::json!({ "y": { "q": 2, "p": 1 }, "x": 1 });
+        assert_eq!(content_hash(&a).unwrap(), content_hash(&b).unwrap());
+    }
+
+    #[test]
+    fn variables_hash_ignores_map_order() {
+        let parse = |ids: Vec<usize>, modes: [&str; 3]| -> LocalVariables {
+            let mut variables = serde_json::Map::new();
+            for id in ids {
+                let mut values = serde_json::Map::new();
+                for mode in modes {
+                    values.insert(mode.to_string(), serde_json::json!(id));
+                }
+                variables.insert(
+                    format!("v{}", id),
+                    serde_json::json!({
+                        "id": format!("v{}", id),
+                        "name": format!("size/{}", id),
+                        "variableCollectionId": "c",
+                        "resolvedType": "FLOAT",
+                        "valuesByMode": values,
+                    }),
+                );
+            }
+            serde_json::from_value(serde_json::json!({ "variables": variables })).unwrap()
+        };
+        let forward = parse((0..16).collect(), ["m1", "m2", "m3"]);
+        let backward = parse((0..16).rev().collect(), ["m3", "m2", "m1"]);
+        assert_eq!(content_hash(&forward).unwrap(), content_hash(&backward).unwrap());
+    }
+}
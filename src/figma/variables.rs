@@ -0,0 +1,140 @@
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use crate::figma::client::{FigmaClient, RETRIES_HEADER};
+use crate::figma::models::{AliasChain, LocalVariables, PublishedVariables};
+use crate::figma::store;
+
+// Which of Figma's two variables endpoints to call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableScope {
+    // Variables defined in the file, with values per mode
+    #[default]
+    Local,
+    // Variables the file publishes to its library, without values
+    Published,
+}
+
+// Struct for query parameters
+#[derive(Deserialize)]
+pub struct VariablesParams {
+    file_key: Option<String>,
+    #[serde(default)]
+    scope: VariableScope,
+}
+
+// Struct for returning response
+#[derive(Serialize)]
+pub struct ApiResponse {
+    file_key: String,
+    scope: VariableScope,
+    message: String,
+    status: String,
+    local: Option<LocalVariables>,
+    published: Option<PublishedVariables>,
+    alias_chains: Vec<AliasChain>,
+}
+
+impl ApiResponse {
+    fn error(file_key: String, scope: VariableScope, message: String) -> Self {
+        ApiResponse {
+            file_key,
+            scope,
+            message,
+            status: "error".to_string(),
+            local: None,
+            published: None,
+            alias_chains: Vec::new(),
+        }
+    }
+}
+
+// GET handler that proxies Figma's variables endpoints and stores the result
+#[get("/variables")]
+pub async fn get_variables(
+    req: HttpRequest,
+    query: web::Query<VariablesParams>,
+    db: web::Data<crate::db::mongo::MongoDb>,
+    client: web::Data<FigmaClient>,
+) -> impl Responder {
+    let scope = query.scope;
+
+    // Check for X-Figma-Token header
+    let token = match req.headers().get("X-Figma-Token") {
+        Some(token_header) => match token_header.to_str() {
+            Ok(token_str) => token_str.to_string(),
+            Err(_) => return HttpResponse::BadRequest().json(ApiResponse::error(
+                "".to_string(),
+                scope,
+                "Invalid token format".to_string(),
+            )),
+        },
+        None => return HttpResponse::Unauthorized().json(ApiResponse::error(
+            "".to_string(),
+            scope,
+            "Missing X-Figma-Token header".to_string(),
+        )),
+    };
+
+    // Get the file_key parameter
+    let file_key = match &query.file_key {
+        Some(id) => id.clone(),
+        None => return HttpResponse::BadRequest().json(ApiResponse::error(
+            "".to_string(),
+            scope,
+            "Missing file_key parameter".to_string(),
+        )),
+    };
+
+    let result = match scope {
+        VariableScope::Local => client.get_local_variables(&token, &file_key).await.map(|fetched| {
+            let message = fetched.describe(format!("Successfully retrieved local variables for Figma file: {}", file_key));
+            let response = ApiResponse {
+                file_key: file_key.clone(),
+                scope,
+                message,
+                status: "success".to_string(),
+                alias_chains: fetched.data.alias_chains(),
+                local: Some(fetched.data),
+                published: None,
+            };
+            (response, fetched.retries)
+        }),
+        VariableScope::Published => client.get_published_variables(&token, &file_key).await.map(|fetched| {
+            let message = fetched.describe(format!("Successfully retrieved published variables for Figma file: {}", file_key));
+            let response = ApiResponse {
+                file_key: file_key.clone(),
+                scope,
+                message,
+                status: "success".to_string(),
+                local: None,
+                published: Some(fetched.data),
+                alias_chains: Vec::new(),
+            };
+            (response, fetched.retries)
+        }),
+    };
+
+    match result {
+        Ok((response, retries)) => {
+            // Store the response in MongoDB
+            let stored = match (&response.local, &response.published) {
+                (Some(local), _) => store::save_local_variables(&db, &file_key, &response.message, local).await,
+                (_, Some(published)) => store::save_published_variables(&db, &file_key, &response.message, published).await,
+                _ => Ok(crate::db::mongo::SnapshotOutcome::Unchanged),
+            };
+            if let Err(e) = stored {
+                eprintln!("Failed to store variables in MongoDB: {}", e);
+            }
+
+            HttpResponse::Ok()
+                .insert_header((RETRIES_HEADER, retries.to_string()))
+                .json(response)
+        }
+        Err(e) => {
+            HttpResponse::build(e.status_code())
+                .insert_header((RETRIES_HEADER, e.retries().to_string()))
+                .json(ApiResponse::error(file_key, scope, e.to_string()))
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::figma::client::{FigmaClient, RETRIES_HEADER};
+use crate::figma::models::{FileVersion, Pagination};
+use crate::figma::store;
+
+// Struct for query parameters
+#[derive(Deserialize)]
+pub struct VersionsParams {
+    file_key: Option<String>,
+    page_size: Option<String>,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+// Struct for returning response
+#[derive(Serialize)]
+pub struct ApiResponse {
+    file_key: String,
+    message: String,
+    status: String,
+    // The page returned by Figma
+    data: Option<Vec<FileVersion>>,
+    pagination: Option<Pagination>,
+    // Every version stored for this file so far
+    stored_count: usize,
+}
+
+// GET handler that proxies Figma's version history and stores it
+#[get("/versions")]
+pub async fn get_versions(
+    req: HttpRequest,
+    query: web::Query<VersionsParams>,
+    db: web::Data<crate::db::mongo::MongoDb>,
+    client: web::Data<FigmaClient>,
+) -> impl Responder {
+    // Check for X-Figma-Token header
+    let token = match req.headers().get("X-Figma-Token") {
+        Some(token_header) => match token_header.to_str() {
+            Ok(token_str) => token_str.to_string(),
+            Err(_) => return HttpResponse::BadRequest().json(ApiResponse {
+                file_key: "".to_string(),
+                message: "Invalid token format".to_string(),
+                status: "error".to_string(),
+                data: None,
+                pagination: None,
+                stored_count: 0,
+            }),
+        },
+        None => return HttpResponse::Unauthorized().json(ApiResponse {
+            file_key: "".to_string(),
+            message: "Missing X-Figma-Token header".to_string(),
+            status: "error".to_string(),
+            data: None,
+            pagination: None,
+            stored_count: 0,
+        }),
+    };
+
+    // Get the file_key parameter
+    let file_key = match &query.file_key {
+        Some(id) => id.clone(),
+        None => return HttpResponse::BadRequest().json(ApiResponse {
+            file_key: "".to_string(),
+            message: "Missing file_key parameter".to_string(),
+            status: "error".to_string(),
+            data: None,
+            pagination: None,
+            stored_count: 0,
+        }),
+    };
+
+    // Pagination parameters are passed through unchanged
+    let mut params = HashMap::new();
+    if let Some(page_size) = &query.page_size {
+        params.insert("page_size", page_size.clone());
+    }
+    if let Some(before) = &query.before {
+        params.insert("before", before.clone());
+    }
+    if let Some(after) = &query.after {
+        params.insert("after", after.clone());
+    }
+
+    match client.get_versions(&token, &file_key, &params).await {
+        Ok(fetched) => {
+            let stored_count = match store::save_versions(&db, &file_key, &fetched.data.versions).await {
+                Ok(stored) => stored.versions.len(),
+                Err(e) => {
+                    eprintln!("Failed to store versions in MongoDB: {}", e);
+                    0
+                }
+            };
+
+            let message = fetched.describe(format!("Successfully retrieved versions for Figma file: {}", file_key));
+            HttpResponse::Ok()
+                .insert_header((RETRIES_HEADER, fetched.retries.to_string()))
+                .json(ApiResponse {
+                    file_key,
+                    message,
+                    status: "success".to_string(),
+                    data: Some(fetched.data.versions),
+                    pagination: fetched.data.pagination,
+                    stored_count,
+                })
+        }
+        Err(e) => {
+            HttpResponse::build(e.status_code())
+                .insert_header((RETRIES_HEADER, e.retries().to_string()))
+                .json(ApiResponse {
+                    file_key,
+                    message: e.to_string(),
+                    status: "error".to_string(),
+                    data: None,
+                    pagination: None,
+                    stored_count: 0,
+                })
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use crate::db::mongo::MongoDb;
+use crate::figma::client::FigmaClient;
+use crate::figma::config::FigmaConfig;
+use crate::figma::store;
+use crate::generator::jobs::JobQueue;
+use crate::generator::routes::regenerate_file;
+
+// Body of a Figma Webhooks V2 request. Only the fields we act on are typed,
+// the rest is kept as sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub event_type: String,
+    // Never stored or echoed back
+    #[serde(skip_serializing, default)]
+    pub passcode: String,
+    pub webhook_id: Option<String>,
+    pub timestamp: Option<String>,
+    pub file_key: Option<String>,
+    pub file_name: Option<String>,
+    // Set on FILE_VERSION_UPDATE
+    pub version_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// Webhook settings, read once at startup and shared through app data
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub passcode: Option<String>,
+    pub token: Option<String>,
+    pub regenerate: bool,
+}
+
+impl WebhookConfig {
+    pub fn from_env() -> Self {
+        let config = FigmaConfig::from_env();
+        WebhookConfig {
+            passcode: config.webhook_passcode,
+            token: config.webhook_token,
+            regenerate: config.webhook_regenerate,
+        }
+    }
+}
+
+// Documents stored in the figma_webhook_events collection
+#[derive(Debug, Serialize)]
+struct StoredWebhookEvent<'a> {
+    #[serde(flatten)]
+    payload: &'a WebhookPayload,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    received_at: DateTime<Utc>,
+}
+
+// Struct for returning response
+#[derive(Serialize)]
+pub struct ApiResponse {
+    file_key: String,
+    message: String,
+    status: String,
+    event_type: String,
+}
+
+// POST handler for Figma webhooks. Figma only waits a few seconds for a
+// reply, so the file is re-synced in the background after answering.
+#[post("/webhook")]
+pub async fn receive_webhook(
+    req: HttpRequest,
+    payload: web::Json<WebhookPayload>,
+    db: web::Data<MongoDb>,
+    client: web::Data<FigmaClient>,
+    jobs: web::Data<JobQueue>,
+    config: web::Data<WebhookConfig>,
+) -> impl Responder {
+    let payload = payload.into_inner();
+    let file_key = payload.file_key.clone().unwrap_or_default();
+
+    let respond = |mut response: actix_web::HttpResponseBuilder, message: String, status: &str| {
+        response.json(ApiResponse {
+            file_key: file_key.clone(),
+            message,
+            status: status.to_string(),
+            event_type: payload.event_type.clone(),
+        })
+    };
+
+    let Some(expected) = &config.passcode else {
+        return respond(
+            HttpResponse::ServiceUnavailable(),
+            "Webhooks are disabled: FIGMA_WEBHOOK_PASSCODE is not set".to_string(),
+            "error",
+        );
+    };
+    if !passcode_matches(expected, &payload.passcode) {
+        return respond(HttpResponse::Unauthorized(), "Invalid webhook passcode".to_string(), "error");
+    }
+
+    if let Err(e) = db
+        .insert_into_collection(
+            "figma_webhook_events",
+            StoredWebhookEvent {
+                payload: &payload,
+                received_at: Utc::now(),
+            },
+        )
+        .await
+    {
+        eprintln!("Failed to store webhook event in MongoDB: {}", e);
+    }
+
+    match payload.event_type.as_str() {
+        "PING" => return respond(HttpResponse::Ok(), "Webhook is reachable".to_string(), "success"),
+        "FILE_UPDATE" | "FILE_VERSION_UPDATE" | "LIBRARY_PUBLISH" => {}
+        other => {
+            return respond(
+                HttpResponse::Ok(),
+                format!("Ignoring {} event", other),
+                "success",
+            )
+        }
+    }
+
+    if file_key.is_empty() {
+        return respond(HttpResponse::BadRequest(), "Missing file_key in payload".to_string(), "error");
+    }
+
+    // An X-Figma-Token header makes local testing possible without the
+    // server-side token
+    let token = req
+        .headers()
+        .get("X-Figma-Token")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| config.token.clone());
+    let Some(token) = token else {
+        // Still 200 so Figma does not keep retrying an event we cannot act on
+        return respond(
+            HttpResponse::Ok(),
+            "Event recorded, but FIGMA_WEBHOOK_TOKEN is not set so the file was not re-fetched".to_string(),
+            "success",
+        );
+    };
+
+    let db = db.get_ref().clone();
+    let client = client.get_ref().clone();
+    let jobs = jobs.get_ref().clone();
+    let regenerate = config.regenerate;
+    let event = payload.clone();
+    actix_web::rt::spawn(async move {
+        sync_file(&db, &client, &jobs, &token, &event, regenerate).await;
+    });
+
+    respond(
+        HttpResponse::Ok(),
+        format!("Re-syncing Figma file {} after {}", file_key, payload.event_type),
+        "success",
+    )
+}
+
+// Compare without stopping at the first differing byte
+fn passcode_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+// Re-fetch the file, every node stored for it, its variables and, for new
+// versions, the version history. Then rebuild generated packages if enabled.
+async fn sync_file(
+    db: &MongoDb,
+    client: &FigmaClient,
+    jobs: &JobQueue,
+    token: &str,
+    event: &WebhookPayload,
+    regenerate: bool,
+) {
+    let Some(file_key) = event.file_key.as_deref() else {
+        return;
+    };
+
+    match client.get_file(token, file_key, &HashMap::new()).await {
+        Ok(fetched) => {
+            let message = fetched.describe(format!(
+                "Re-fetched Figma file {} after {}",
+                file_key, event.event_type
+            ));
+            match store::save_file_snapshot(db, file_key, &message, &fetched.data, true).await {
+                Ok(outcome) => println!("{}: {:?}", message, outcome),
+                Err(e) => eprintln!("Failed to store Figma file in MongoDB: {}", e),
+            }
+        }
+        Err(e) => {
+            eprintln!("Webhook re-fetch of Figma file {} failed: {}", file_key, e);
+            return;
+        }
+    }
+
+    // Nodes fetched individually are kept current too, since generation
+    // prefers them over the full file
+    let filter = doc! { "file_key": file_key, "current": true };
+    let node_ids = match db.distinct_in_collection("figma_nodes", "node_id", filter).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            eprintln!("Failed to list stored nodes for {}: {}", file_key, e);
+            Vec::new()
+        }
+    };
+    for node_id in node_ids {
+        match client.get_nodes(token, file_key, &node_id).await {
+            Ok(fetched) => {
+                let message = fetched.describe(format!(
+                    "Re-fetched node {} of Figma file {} after {}",
+                    node_id, file_key, event.event_type
+                ));
+                if let Err(e) =
+                    store::save_nodes_snapshot(db, file_key, &node_id, &message, &fetched.data).await
+                {
+                    eprintln!("Failed to store Figma nodes in MongoDB: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Webhook re-fetch of node {} failed: {}", node_id, e),
+        }
+    }
+
+    // Variables are only available on some Figma plans, so a failure here
+    // is logged and skipped
+    match client.get_local_variables(token, file_key).await {
+        Ok(fetched) => {
+            let message = fetched.describe(format!(
+                "Re-fetched local variables of Figma file {} after {}",
+                file_key, event.event_type
+            ));
+            if let Err(e) = store::save_local_variables(db, file_key, &message, &fetched.data).await {
+                eprintln!("Failed to store variables in MongoDB: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Webhook re-fetch of variables for {} failed: {}", file_key, e),
+    }
+
+    if event.event_type == "FILE_VERSION_UPDATE" {
+        match client.get_versions(token, file_key, &HashMap::new()).await {
+            Ok(fetched) => {
+                if let Err(e) = store::save_versions(db, file_key, &fetched.data.versions).await {
+                    eprintln!("Failed to store versions in MongoDB: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Webhook re-fetch of versions for {} failed: {}", file_key, e),
+        }
+    }
+
+    if regenerate {
+        match regenerate_file(db, jobs, file_key).await {
+            Ok(started) => println!("Queued {} package rebuilds for Figma file {}", started, file_key),
+            Err(e) => eprintln!("Failed to regenerate packages for {}: {}", file_key, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use serde_json::json;
+
+    async fn deliver(passcode: Option<&str>, token: Option<&str>, body: Value) -> (u16, Value) {
+        let config = WebhookConfig {
+            passcode: passcode.map(str::to_string),
+            token: token.map(str::to_string),
+            regenerate: false,
+        };
+        let db = MongoDb::unreachable().await;
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .app_data(web::Data::new(FigmaClient::from_env().unwrap()))
+                .app_data(web::Data::new(JobQueue::new(db, 1)))
+                .app_data(web::Data::new(config))
+                .service(receive_webhook),
+        )
+        .await;
+        let request = test::TestRequest::post().uri("/webhook").set_json(body).to_request();
+        let response = test::call_service(&app, request).await;
+        let status = response.status().as_u16();
+        (status, test::read_body_json(response).await)
+    }
+
+    fn event(event_type: &str, passcode: &str) -> Value {
+        json!({ "event_type": event_type, "passcode": passcode, "file_key": "abc", "webhook_id": "1" })
+    }
+
+    #[test]
+    async fn deliveries_are_refused_without_a_configured_passcode() {
+        let (status, body) = deliver(None, None, event("PING", "")).await;
+        assert_eq!(status, 503);
+        assert_eq!(body["message"], "Webhooks are disabled: FIGMA_WEBHOOK_PASSCODE is not set");
+    }
+
+    #[test]
+    async fn deliveries_with_the_wrong_passcode_are_refused() {
+        for passcode in ["wrong", "secre", "secret!", ""] {
+            let (status, body) = deliver(Some("secret"), None, event("PING", passcode)).await;
+            assert_eq!(status, 401);
+            assert_eq!(body["message"], "Invalid webhook passcode");
+        }
+    }
+
+    #[test]
+    async fn pings_are_answered() {
+        let (status, body) = deliver(Some("secret"), None, event("PING", "secret")).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["message"], "Webhook is reachable");
+        assert_eq!(body["event_type"], "PING");
+    }
+
+    #[test]
+    async fn other_events_are_acknowledged_and_ignored() {
+        let (status, body) = deliver(Some("secret"), None, event("FILE_COMMENT", "secret")).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["message"], "Ignoring FILE_COMMENT event");
+    }
+
+    #[test]
+    async fn file_events_need_a_file_key_and_a_token() {
+        let body = json!({ "event_type": "FILE_UPDATE", "passcode": "secret" });
+        let (status, body) = deliver(Some("secret"), None, body).await;
+        assert_eq!(status, 400);
+        assert_eq!(body["message"], "Missing file_key in payload");
+
+        let (status, body) = deliver(Some("secret"), None, event("FILE_UPDATE", "secret")).await;
+        assert_eq!(status, 200);
+        assert_eq!(
+            body["message"],
+            "Event recorded, but FIGMA_WEBHOOK_TOKEN is not set so the file was not re-fetched"
+        );
+    }
+
+    #[test]
+    async fn passcodes_are_never_stored() {
+        let payload: WebhookPayload = serde_json::from_value(event("PING", "secret")).unwrap();
+        let stored = serde_json::to_value(&payload).unwrap();
+        assert!(stored.get("passcode").is_none());
+        assert_eq!(stored["webhook_id"], "1");
+    }
+}
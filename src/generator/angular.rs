@@ -0,0 +1,198 @@
+// Angular output: a standalone component with inputs, a click output and
+// component-scoped styles, built with ng-packagr
+use tera::{Context, Tera};
+use crate::generator::builder::{GeneratedPackage, GeneratedFile};
+use crate::generator::framework::{Framework, Styling};
+use crate::generator::markup::{class_names, render_stylesheet, render_template, tag_name, Dialect};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::ElementNode;
+use crate::tokens::css::render_files;
+use crate::tokens::model::TokenSet;
+
+pub fn add_templates(tera: &mut Tera) {
+    tera.add_raw_template("angular_component", r##"import { Component, EventEmitter, Input, Output } from '@angular/core';
+
+@Component({
+  selector: '{{ selector }}',
+  standalone: true,
+  templateUrl: './{{ file_stem }}.html',
+  styleUrls: ['./{{ file_stem }}.css'],
+})
+export class {{ name }}Component {
+  @Input() disabled = false;
+
+  // Not named "click", which would also catch the native event bubbling from the host
+  @Output() clicked = new EventEmitter<MouseEvent>();
+
+  onClick(event: MouseEvent) {
+    if (!this.disabled) {
+      this.clicked.emit(event);
+    }
+  }
+}
+"##).expect("Failed to add angular_component template");
+
+    tera.add_raw_template("angular_package_json", r##"{
+  "name": "{{ package_name }}",
+  "version": "{{ version }}",
+  "description": "{{ name }} component",
+  "module": "dist/fesm2022/{{ flat_module }}.mjs",
+  "typings": "dist/index.d.ts",
+  "style": "styles/tokens.css",
+  "files": [
+    "dist",
+    "styles"
+  ],
+  "sideEffects": false,
+  "scripts": {
+    "build": "ng-packagr -p ng-package.json",
+    "prepublishOnly": "npm run build"
+  },
+  "peerDependencies": {
+    "@angular/common": "^17.0.0",
+    "@angular/core": "^17.0.0"
+  },
+  "dependencies": {
+    "tslib": "^2.5.0"
+  },
+  "devDependencies": {
+    "@angular/common": "^17.0.0",
+    "@angular/compiler": "^17.0.0",
+    "@angular/compiler-cli": "^17.0.0",
+    "@angular/core": "^17.0.0",
+    "ng-packagr": "^17.0.0",
+    "rxjs": "^7.8.0",
+    "typescript": "~5.4.0",
+    "zone.js": "^0.14.0"
+  },
+  "license": "MIT"
+}
+"##).expect("Failed to add angular_package_json template");
+}
+
+// Angular Package Format output under dist/, with index.ts as the entry
+const NG_PACKAGE: &str = r#"{
+  "$schema": "./node_modules/ng-packagr/ng-package.schema.json",
+  "dest": "dist",
+  "lib": {
+    "entryFile": "src/index.ts"
+  }
+}
+"#;
+
+fn dialect() -> Dialect {
+    Dialect {
+        root_attributes: vec![
+            "[attr.aria-disabled]=\"disabled || null\"".to_string(),
+            "(click)=\"onClick($event)\"".to_string(),
+        ],
+        children: "<ng-content></ng-content>".to_string(),
+        interpolate: |literal| format!("{{{{ {} }}}}", literal),
+    }
+}
+
+pub fn generate(
+    tera: &Tera,
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    let selector = tag_name(request.tag_prefix.as_deref(), &request.name)?;
+    let classes = class_names(root);
+
+    // Angular style guide file names: custom-button.component.ts
+    let framework = Framework::Angular;
+    let component_filename = framework.component_filename(&request.name, request.typescript);
+    let file_stem = component_filename.trim_end_matches(".ts").to_string();
+    // ng-packagr names bundles after the package: @acme/button is acme-button
+    let flat_module = request.package_name.trim_start_matches('@').replace('/', "-");
+
+    let mut context = Context::new();
+    context.insert("name", &request.name);
+    context.insert("selector", &selector);
+    context.insert("file_stem", &file_stem);
+    context.insert("flat_module", &flat_module);
+    context.insert("package_name", &request.package_name);
+    context.insert("version", &request.version);
+
+    let component_code = tera.render("angular_component", &context)
+        .map_err(|e| format!("Component template error: {}", e))?;
+
+    let package_json = tera.render("angular_package_json", &context)
+        .map_err(|e| format!("Package.json template error: {}", e))?;
+
+    let index_ts = format!(
+        "export {{ {}Component }} from './{}';\nexport * from './tokens';\nexport * from './themes';\n",
+        request.name, file_stem
+    );
+
+    Ok(GeneratedPackage {
+        framework,
+        styling: Styling::Inline,
+        component_filename,
+        component_code,
+        package_json,
+        index_ts,
+        tokens: render_files(tokens),
+        extra_files: vec![
+            GeneratedFile::new(format!("src/{}.html", file_stem), render_template(root, &classes, &dialect(), 0)),
+            GeneratedFile::new(format!("src/{}.css", file_stem), render_stylesheet(root, &classes)),
+            GeneratedFile::new("ng-package.json", NG_PACKAGE),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::test_support::{button, generate, request};
+
+    fn file<'a>(package: &'a crate::generator::builder::GeneratedPackage, path: &str) -> &'a str {
+        let file = package.extra_files.iter().find(|file| file.path == path);
+        &file.unwrap_or_else(|| panic!("{} was not generated", path)).contents
+    }
+
+    #[test]
+    fn layers_become_a_template_and_stylesheet_beside_the_component() {
+        let package = generate(&request("angular"), &button());
+
+        assert!(package.component_code.contains(
+            "@Component({\n  selector: 'ui-button',\n  standalone: true,\n  templateUrl: './button.component.html',\n  \
+             styleUrls: ['./button.component.css'],\n})\nexport class ButtonComponent {\n"
+        ));
+        assert!(package.component_code.contains("  @Output() clicked = new EventEmitter<MouseEvent>();\n"));
+        assert_eq!(
+            file(&package, "src/button.component.html"),
+            "<div\n  class=\"button\"\n  data-figma-id=\"1:1\"\n  [attr.aria-disabled]=\"disabled || null\"\n  \
+             (click)=\"onClick($event)\"\n>\n  <span\n    class=\"label\"\n    data-figma-id=\"1:2\"\n  >\n    \
+             {{ 'Click me' }}\n  </span>\n  <ng-content></ng-content>\n</div>\n"
+        );
+        assert!(file(&package, "src/button.component.css").starts_with(".button {\n  background: #0055ff;\n"));
+        assert_eq!(package.component_filename, "button.component.ts");
+        assert!(package.index_ts.starts_with("export { ButtonComponent } from './button.component';\n"));
+    }
+
+    #[test]
+    fn file_names_follow_the_style_guide_and_selectors_the_prefix() {
+        let mut request = request("angular");
+        request.name = "CustomButton".to_string();
+        request.tag_prefix = Some("acme".to_string());
+        request.typescript = false;
+        let package = generate(&request, &button());
+
+        // Angular libraries are always TypeScript
+        assert_eq!(package.component_filename, "custom-button.component.ts");
+        assert!(package.component_code.contains("  selector: 'acme-custom-button',\n"));
+        assert!(package.component_code.contains("export class CustomButtonComponent {\n"));
+        assert!(package.extra_files.iter().any(|file| file.path == "src/custom-button.component.html"));
+    }
+
+    #[test]
+    fn the_package_is_built_with_ng_packagr() {
+        let package = generate(&request("angular"), &button());
+        let package_json: serde_json::Value = serde_json::from_str(&package.package_json).unwrap();
+        assert_eq!(package_json["module"], "dist/fesm2022/acme-button.mjs");
+        assert_eq!(package_json["scripts"]["build"], "ng-packagr -p ng-package.json");
+        let ng_package: serde_json::Value = serde_json::from_str(file(&package, "ng-package.json")).unwrap();
+        assert_eq!(ng_package["lib"]["entryFile"], "src/index.ts");
+    }
+}
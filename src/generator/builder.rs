@@ -1,8 +1,15 @@
 use tera::{Tera, Context};
 use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
+use crate::generator::framework::{Framework, Styling};
+use crate::generator::markup::{class_names, css_property, element_from_config, render_stylesheet};
 use crate::generator::models::CreateComponentRequest;
-use std::collections::HashMap;
+use crate::generator::node_tree::{apply_tokens, component_name, render_jsx, ElementNode, JsxStyling};
+use crate::generator::tailwind::{self, TailwindTheme};
+use crate::generator::{angular, lit, react_native, svelte, vue};
+use crate::tokens::css::{render_files, TokenFiles, TokenLookup};
+use crate::tokens::model::TokenSet;
 
 // Initialize Tera as a global static
 pub static TEMPLATES: Lazy<Mutex<Tera>> = Lazy::new(|| {
@@ -42,6 +49,73 @@ const {{ name }}{% if typescript %}: React.FC<{{ name }}Props>{% endif %} = ({ c
 export default {{ name }};
 "##).expect("Failed to add react_component template");
 
+    // React component rendered from a Figma node tree
+    tera.add_raw_template("react_node_component", r##"
+import React from 'react';
+{%- if imports %}
+{{ imports }}
+{%- endif %}
+{%- if typescript %}
+
+interface {{ name }}Props {
+  children?: React.ReactNode;
+  onClick?: () => void;
+  className?: string;
+}
+{%- endif %}
+{%- if preamble %}
+
+{{ preamble }}
+{%- endif %}
+
+const {{ name }}{% if typescript %}: React.FC<{{ name }}Props>{% endif %} = ({ children, onClick, className }) => {
+  return (
+{{ jsx }}  );
+};
+
+export default {{ name }};
+"##).expect("Failed to add react_node_component template");
+
+    // Theme context for the generated package; the data-theme wrapper switches
+    // the custom properties from tokens.css
+    tera.add_raw_template("react_theme_provider", r##"
+import React, { createContext, useContext } from 'react';
+import { themes, defaultTheme{% if typescript %}, ThemeName, Theme{% endif %} } from './themes';
+{%- if typescript %}
+
+interface ThemeContextValue {
+  name: ThemeName;
+  theme: Theme;
+}
+
+export interface ThemeProviderProps {
+  theme?: ThemeName;
+  children?: React.ReactNode;
+}
+{%- endif %}
+
+const ThemeContext = createContext{% if typescript %}<ThemeContextValue>{% endif %}({
+  name: defaultTheme,
+  theme: themes[defaultTheme],
+});
+
+const wrapperStyle{% if typescript %}: React.CSSProperties{% endif %} = { display: 'contents' };
+
+export const ThemeProvider{% if typescript %}: React.FC<ThemeProviderProps>{% endif %} = ({ theme = defaultTheme, children }) => {
+  const value = { name: theme, theme: themes[theme] };
+
+  return (
+    <ThemeContext.Provider value={value}>
+      <div data-theme={theme} style={wrapperStyle}>
+        {children}
+      </div>
+    </ThemeContext.Provider>
+  );
+};
+
+export const useTheme = () => useContext(ThemeContext);
+"##).expect("Failed to add react_theme_provider template");
+
     // Add package.json template
     tera.add_raw_template("package_json", r##"{
   "name": "{{ package_name }}",
@@ -50,20 +124,30 @@ export default {{ name }};
   "main": "dist/index.js",
   "module": "dist/index.esm.js",
   "types": "dist/index.d.ts",
+  "style": "{% if styling == "css-modules" %}dist/index.css{% else %}styles/tokens.css{% endif %}",
   "files": [
-    "dist"
-  ],
+    "dist",
+    "styles"{% if styling == "tailwind" %},
+    "tailwind.preset.js"{% endif %}
+  ],{% if styling == "css-modules" %}
+  "sideEffects": [
+    "*.css"
+  ],{% endif %}
   "scripts": {
     "build": "rollup -c",
     "prepublishOnly": "npm run build"
   },
   "peerDependencies": {
     "react": "^16.8.0 || ^17.0.0 || ^18.0.0",
-    "react-dom": "^16.8.0 || ^17.0.0 || ^18.0.0"
+    "react-dom": "^16.8.0 || ^17.0.0 || ^18.0.0"{% if styling == "styled-components" %},
+    "styled-components": "^6.0.0"{% endif %}
   },
   "devDependencies": {
-    "@types/react": "^18.0.0",
-    "@rollup/plugin-typescript": "^11.1.0",
+    "@types/react": "^18.0.0",{% if styling == "styled-components" %}
+    "styled-components": "^6.0.0",{% endif %}
+    "@rollup/plugin-typescript": "^11.1.0",{% if styling == "css-modules" %}
+    "postcss": "^8.4.0",
+    "rollup-plugin-postcss": "^4.0.2",{% endif %}
     "rollup": "^3.20.0",
     "typescript": "^5.0.0",
     "tslib": "^2.5.0"
@@ -72,26 +156,77 @@ export default {{ name }};
 }
 "##).expect("Failed to add package_json template");
 
+    vue::add_templates(&mut tera);
+    svelte::add_templates(&mut tera);
+    lit::add_templates(&mut tera);
+    angular::add_templates(&mut tera);
+    react_native::add_templates(&mut tera);
+
     Mutex::new(tera)
 });
 
 // Add new struct to hold all generated files
 pub struct GeneratedPackage {
+    pub framework: Framework,
+    pub styling: Styling,
+    pub component_filename: String,
     pub component_code: String,
     pub package_json: String,
     pub index_ts: String,
+    pub tokens: TokenFiles,
+    // Framework-specific files, e.g. src/ThemeProvider.tsx or vite.config.ts
+    pub extra_files: Vec<GeneratedFile>,
+}
+
+pub struct GeneratedFile {
+    // Relative to the package root
+    pub path: String,
+    pub contents: String,
+}
+
+impl GeneratedFile {
+    pub fn new(path: impl Into<String>, contents: impl Into<String>) -> Self {
+        GeneratedFile {
+            path: path.into(),
+            contents: contents.into(),
+        }
+    }
 }
 
 // Update function to generate all files
-pub fn generate_package_files(request: &CreateComponentRequest) -> Result<GeneratedPackage, String> {
+pub fn generate_package_files(
+    request: &CreateComponentRequest,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    let framework = Framework::parse(&request.framework)?;
+    let styling = parse_styling(request, framework)?;
     let tera = TEMPLATES.lock().unwrap();
     let mut context = Context::new();
+
+    // Literal config values that match a token become custom properties
+    let lookup = TokenLookup::new(tokens);
+    let element = || element_from_config(request, &lookup);
+    match framework {
+        Framework::React if styling == Styling::Inline => {}
+        Framework::React => return react_node_package(&tera, request, &element(), tokens, styling),
+        Framework::Vue => return vue::generate(&tera, request, &element(), tokens),
+        Framework::Svelte => return svelte::generate(&tera, request, &element(), tokens),
+        Framework::Lit => return lit::generate(&tera, request, &element(), tokens),
+        Framework::Angular => return angular::generate(&tera, request, &element(), tokens),
+        Framework::ReactNative => return react_native::generate(&tera, request, &element(), tokens),
+    }
+    let config: HashMap<&String, String> = request
+        .config
+        .iter()
+        .map(|(key, value)| (key, lookup.style_value(key, value).unwrap_or_else(|| value.clone())))
+        .collect();
     
     // Common context
     context.insert("name", &request.name);
     context.insert("component_type", &request.component_type);
     context.insert("typescript", &request.typescript);
-    context.insert("config", &request.config);
+    context.insert("config", &config);
+    context.insert("styling", styling.as_str());
     context.insert("package_name", &request.package_name);
     context.insert("version", &request.version);
     
@@ -103,12 +238,259 @@ pub fn generate_package_files(request: &CreateComponentRequest) -> Result<Genera
     let package_json = tera.render("package_json", &context)
         .map_err(|e| format!("Package.json template error: {}", e))?;
     
-    // Generate index.ts (simple export)
-    let index_ts = format!("export {{ default as {} }} from './{}';", &request.name, &request.name);
+    let theme_provider = tera.render("react_theme_provider", &context)
+        .map_err(|e| format!("Theme provider template error: {}", e))?;
+
+    // Generate index.ts (component, tokens and themes)
+    let index_ts = index_ts(&request.name);
     
     Ok(GeneratedPackage {
+        framework,
+        styling,
+        component_filename: framework.component_filename(&request.name, request.typescript),
         component_code,
         package_json,
         index_ts,
+        tokens: render_files(tokens),
+        extra_files: vec![theme_provider_file(request, theme_provider)],
+    })
+}
+
+// Generate all files for a component built from a Figma node tree
+pub fn generate_package_files_from_node(
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    let framework = Framework::parse(&request.framework)?;
+    let styling = parse_styling(request, framework)?;
+    let tera = TEMPLATES.lock().unwrap();
+
+    let mut root = root.clone();
+    apply_tokens(&mut root, &TokenLookup::new(tokens));
+    match framework {
+        Framework::React => react_node_package(&tera, request, &root, tokens, styling),
+        Framework::Vue => vue::generate(&tera, request, &root, tokens),
+        Framework::Svelte => svelte::generate(&tera, request, &root, tokens),
+        Framework::Lit => lit::generate(&tera, request, &root, tokens),
+        Framework::Angular => angular::generate(&tera, request, &root, tokens),
+        Framework::ReactNative => react_native::generate(&tera, request, &root, tokens),
+    }
+}
+
+// React component rendered from an element tree, with the styles inline,
+// as Tailwind classes or as styled components
+fn react_node_package(
+    tera: &Tera,
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+    styling: Styling,
+) -> Result<GeneratedPackage, String> {
+    let framework = Framework::React;
+    let mut context = Context::new();
+    let mut extra_files = Vec::new();
+
+    let (jsx, imports, preamble) = match styling {
+        Styling::Inline => (render_jsx(root, 2, &JsxStyling::Inline), String::new(), String::new()),
+        Styling::Tailwind => {
+            let theme = match &request.tailwind_theme {
+                Some(scales) => TailwindTheme::new(tokens).with_scales(scales)?,
+                None => TailwindTheme::new(tokens),
+            };
+            let classes: Vec<String> = root
+                .flatten()
+                .iter()
+                .map(|element| theme.classes(&element.styles).join(" "))
+                .collect();
+            extra_files.push(GeneratedFile::new("tailwind.preset.js", theme.preset(&request.package_name)));
+            extra_files.push(GeneratedFile::new("README.md", tailwind::readme(&request.package_name)));
+            (render_jsx(root, 2, &JsxStyling::Classes(&classes)), String::new(), String::new())
+        }
+        Styling::CssModules => {
+            let classes = class_names(root);
+            let stylesheet = format!("{}.module.css", request.name);
+            extra_files.push(GeneratedFile::new(format!("src/{}", stylesheet), render_stylesheet(root, &classes)));
+            if request.typescript {
+                extra_files.push(GeneratedFile::new("src/css-modules.d.ts", CSS_MODULES_DECLARATION));
+            }
+            extra_files.push(GeneratedFile::new("README.md", css_modules_readme(&request.package_name)));
+            (
+                render_jsx(root, 2, &JsxStyling::CssModules(&classes)),
+                format!("import styles from './{}';", stylesheet),
+                String::new(),
+            )
+        }
+        Styling::StyledComponents => {
+            let names = styled_names(root);
+            (
+                render_jsx(root, 2, &JsxStyling::Components(&names)),
+                "import styled from 'styled-components';".to_string(),
+                styled_components(root, &names),
+            )
+        }
+    };
+
+    context.insert("name", &request.name);
+    context.insert("typescript", &request.typescript);
+    context.insert("styling", styling.as_str());
+    context.insert("package_name", &request.package_name);
+    context.insert("version", &request.version);
+    context.insert("jsx", &jsx);
+    context.insert("imports", &imports);
+    context.insert("preamble", &preamble);
+
+    let component_code = tera.render("react_node_component", &context)
+        .map_err(|e| format!("Component template error: {}", e))?;
+
+    let package_json = tera.render("package_json", &context)
+        .map_err(|e| format!("Package.json template error: {}", e))?;
+
+    let theme_provider = tera.render("react_theme_provider", &context)
+        .map_err(|e| format!("Theme provider template error: {}", e))?;
+    extra_files.push(theme_provider_file(request, theme_provider));
+
+    Ok(GeneratedPackage {
+        framework,
+        styling,
+        component_filename: framework.component_filename(&request.name, request.typescript),
+        component_code,
+        package_json,
+        index_ts: index_ts(&request.name),
+        tokens: render_files(tokens),
+        extra_files,
     })
-}
\ No newline at end of file
+}
+
+// A Tailwind theme only applies to Tailwind output
+fn parse_styling(request: &CreateComponentRequest, framework: Framework) -> Result<Styling, String> {
+    let styling = Styling::parse(request.styling.as_deref(), framework)?;
+    if request.tailwind_theme.is_some() && styling != Styling::Tailwind {
+        return Err(format!("tailwind_theme requires styling tailwind, not {}", styling.as_str()));
+    }
+    Ok(styling)
+}
+
+// Lets TypeScript import the stylesheet's class map
+const CSS_MODULES_DECLARATION: &str = r#"declare module '*.module.css' {
+  const classes: { readonly [key: string]: string };
+  export default classes;
+}
+"#;
+
+// The bundle doesn't inject its styles, so consumers import them once
+fn css_modules_readme(package_name: &str) -> String {
+    let mut out = format!("# {}\n\n", package_name);
+    out.push_str("The component styles are bundled into `dist/index.css`, which the JavaScript\n");
+    out.push_str("does not inject. Import it once, together with the design tokens:\n\n");
+    out.push_str(&format!("```js\nimport '{}/dist/index.css';\n", package_name));
+    out.push_str(&format!("import '{}/styles/tokens.css';\n```\n", package_name));
+    out
+}
+
+// Component names for the elements. "label-2" and "label2" are distinct
+// classes but would both be StyledLabel2, so names are deduplicated again.
+fn styled_names(root: &ElementNode) -> Vec<String> {
+    let mut used: HashSet<String> = HashSet::new();
+    class_names(root)
+        .iter()
+        .map(|class| {
+            let base = format!("Styled{}", component_name(class));
+            let mut name = base.clone();
+            let mut count = 1;
+            while !used.insert(name.clone()) {
+                count += 1;
+                name = format!("{}{}", base, count);
+            }
+            name
+        })
+        .collect()
+}
+
+// One styled component per element, named like its layer. The call form
+// works for any tag; styled.card would be undefined for a "card" component.
+fn styled_components(root: &ElementNode, names: &[String]) -> String {
+    root.flatten()
+        .iter()
+        .zip(names)
+        .map(|(element, name)| {
+            let rules: String = element
+                .styles
+                .iter()
+                .map(|(property, value)| format!("  {}: {};\n", css_property(property), template_text(value)))
+                .collect();
+            format!("const {} = styled('{}')`\n{}`;", name, element.tag, rules)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// Text inside a JS template literal: a backtick would end it and ${ would
+// start an interpolation
+fn template_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${")
+}
+
+fn theme_provider_file(request: &CreateComponentRequest, code: String) -> GeneratedFile {
+    let path = if request.typescript { "src/ThemeProvider.tsx" } else { "src/ThemeProvider.jsx" };
+    GeneratedFile::new(path, code)
+}
+
+fn index_ts(name: &str) -> String {
+    format!(
+        "export {{ default as {} }} from './{}';\nexport * from './tokens';\nexport * from './themes';\nexport {{ ThemeProvider, useTheme }} from './ThemeProvider';\n",
+        name, name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::test_support::{button, element, generate, request};
+
+    #[test]
+    fn inline_styles_render_as_style_objects() {
+        let package = generate(&request("react"), &button());
+        let code = &package.component_code;
+
+        assert!(code.contains("data-figma-id=\"1:1\"\n      style={{ background: '#0055ff', borderRadius: '4px', padding: '8px 16px' }}"));
+        assert!(code.contains("style={{ color: '#ffffff', fontSize: '14px' }}\n      >\n        {'Click me'}"));
+        assert_eq!(package.component_filename, "Button.tsx");
+        assert!(package.index_ts.contains("export { ThemeProvider, useTheme } from './ThemeProvider';"));
+        assert!(package.extra_files.iter().any(|file| file.path == "src/ThemeProvider.tsx"));
+    }
+
+    #[test]
+    fn styled_components_escape_template_literal_values() {
+        let mut request = request("react");
+        request.styling = Some("styled-components".to_string());
+        let root = element("1:1", "Card", &[("content", "'`${alert(1)}` \\\\'")], None, vec![]);
+        let code = generate(&request, &root).component_code;
+
+        assert!(code.contains("const StyledCard = styled('div')`\n  content: '\\`\\${alert(1)}\\` \\\\\\\\';\n`;"));
+        assert!(code.contains("<StyledCard\n"));
+    }
+
+    #[test]
+    fn tailwind_classes_that_would_break_the_attribute_become_expressions() {
+        let mut request = request("react");
+        request.styling = Some("tailwind".to_string());
+        let root = element(
+            "1:1",
+            "Card",
+            &[("display", "flex")],
+            None,
+            vec![
+                element("1:2", "Quote", &[("quotes", "\"a\" &amp;")], None, vec![]),
+                element("1:3", "Label", &[("fontFamily", "Inter")], Some("Hi"), vec![]),
+            ],
+        );
+        let code = generate(&request, &root).component_code;
+
+        assert!(code.contains("className={[\'flex\', className].filter(Boolean).join(' ')}"));
+        assert!(code.contains("className={'[quotes:\"a\"_&amp;]'}"));
+        assert!(code.contains("className=\"font-['Inter']\""));
+    }
+}
@@ -2,22 +2,27 @@ use std::env;
 
 pub struct GeneratorConfig {
     pub npm_path: String,
-    pub use_docker: bool,
     pub docker_image: String,
+    // Longest a single npm step may run before its container is killed
     pub build_timeout_seconds: u64,
+    // Package builds allowed to run at the same time
+    pub build_workers: usize,
 }
 
 impl GeneratorConfig {
     pub fn from_env() -> Self {
         GeneratorConfig {
             npm_path: env::var("NPM_PATH").unwrap_or_else(|_| "npm".to_string()),
-            use_docker: env::var("USE_DOCKER_BUILD")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .unwrap_or(true),
             docker_image: env::var("DOCKER_BUILD_IMAGE")
                 .unwrap_or_else(|_| "node-builder:latest".to_string()),
-            build_timeout_seconds: 300,
+            build_timeout_seconds: env::var("BUILD_TIMEOUT_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(300),
+            build_workers: env::var("BUILD_WORKERS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(2),
         }
     }
 }
\ No newline at end of file
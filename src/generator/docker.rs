@@ -1,8 +1,17 @@
-use std::process::Command;
-use std::path::{Path, PathBuf};
-use std::io;
+use std::process::{Child, Command, Stdio};
+use std::path::Path;
+use std::io::{self, BufRead, BufReader, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
 use crate::generator::config::GeneratorConfig;
 
+// How often a running step is checked for its timeout and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// Tries at removing the container of a stopped step
+const REMOVE_ATTEMPTS: usize = 5;
+
 pub struct DockerBuilder {
     config: GeneratorConfig,
 }
@@ -45,7 +54,7 @@ impl DockerBuilder {
         }
         
         let output = Command::new("docker")
-            .args(&[
+            .args([
                 "build",
                 "-f", dockerfile_path.to_str().unwrap(),
                 "-t", &self.config.docker_image,
@@ -57,80 +66,186 @@ impl DockerBuilder {
         if output.status.success() {
             Ok("Docker image built successfully".to_string())
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(io::Error::other(
                 format!("Docker build failed: {}", String::from_utf8_lossy(&output.stderr))
             ))
         }
     }
     
-    pub fn run_npm_install(&self, project_path: &Path) -> Result<String, io::Error> {
+    // Run an npm command in the build image with the project mounted, in a
+    // container with the given name. Each output line is passed to on_line
+    // as it is printed, and both streams are also returned. The container
+    // is killed when the build timeout passes or `cancelled` is set.
+    // Err only when docker could not be started.
+    pub fn run_npm<F>(
+        &self,
+        project_path: &Path,
+        container: &str,
+        args: &[&str],
+        cancelled: &AtomicBool,
+        on_line: F,
+    ) -> Result<NpmOutput, io::Error>
+    where
+        F: Fn(LogStream, &str) + Sync,
+    {
         let absolute_path = project_path.canonicalize()?;
         
-        let output = Command::new("docker")
-            .args(&[
+        let mut child = Command::new("docker")
+            .args([
                 "run",
                 "--rm",
+                "--name", container,
                 "-v", &format!("{}:/build", absolute_path.display()),
                 &self.config.docker_image,
-                "npm", "install"
+                "npm",
             ])
-            .output()?;
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
         
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("npm install failed: {}", String::from_utf8_lossy(&output.stderr))
-            ))
+        let timeout = self.config.build_timeout_seconds;
+        let deadline = Instant::now() + Duration::from_secs(timeout);
+        
+        // Both pipes are drained at once so neither can fill up and stall npm
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        std::thread::scope(|scope| {
+            let stdout = scope.spawn(|| read_lines(stdout, LogStream::Stdout, &on_line));
+            let stderr = scope.spawn(|| read_lines(stderr, LogStream::Stderr, &on_line));
+            
+            let exit = loop {
+                let status = match child.try_wait() {
+                    Ok(status) => status,
+                    Err(e) => {
+                        // Returning now would leave the scope waiting on npm
+                        self.stop(&mut child, container);
+                        return Err(e);
+                    }
+                };
+                if let Some(status) = status {
+                    break if status.success() { NpmExit::Success } else { NpmExit::Failure };
+                }
+                let stop = if cancelled.load(Ordering::SeqCst) {
+                    Some(NpmExit::Cancelled)
+                } else if Instant::now() >= deadline {
+                    Some(NpmExit::TimedOut(timeout))
+                } else {
+                    None
+                };
+                if let Some(stop) = stop {
+                    self.stop(&mut child, container);
+                    break stop;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            };
+            
+            Ok(NpmOutput {
+                exit,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            })
+        })
+    }
+    
+    // Killing the docker client alone leaves the container running, so the
+    // container is killed first and removed once the client is gone
+    fn stop(&self, child: &mut Child, container: &str) {
+        let killed = self.kill_container(container);
+        let _ = child.kill();
+        let _ = child.wait();
+        self.remove_container(container, killed);
+    }
+    
+    fn kill_container(&self, container: &str) -> bool {
+        match Command::new("docker").args(["kill", container]).output() {
+            Ok(output) if output.status.success() => {
+                println!("Killed container {}", container);
+                true
+            }
+            Ok(output) => {
+                eprintln!(
+                    "Failed to kill container {}: {}",
+                    container,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                false
+            }
+            Err(e) => {
+                eprintln!("Failed to kill container {}: {}", container, e);
+                false
+            }
         }
     }
     
-    pub fn run_npm_build(&self, project_path: &Path) -> Result<String, io::Error> {
-        let absolute_path = project_path.canonicalize()?;
-        
-        let output = Command::new("docker")
-            .args(&[
-                "run",
-                "--rm",
-                "-v", &format!("{}:/build", absolute_path.display()),
-                &self.config.docker_image,
-                "npm", "run", "build"
-            ])
-            .output()?;
-        
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("npm build failed: {}", String::from_utf8_lossy(&output.stderr))
-            ))
+    // A step stopped right after it started may have had no container to
+    // kill yet, while the daemon can still create it after the client is
+    // gone, so removal keeps being retried until the attempts run out
+    fn remove_container(&self, container: &str, killed: bool) {
+        for _ in 0..REMOVE_ATTEMPTS {
+            let _ = Command::new("docker").args(["rm", "-f", container]).output();
+            std::thread::sleep(POLL_INTERVAL);
+            if killed && !self.container_exists(container) {
+                return;
+            }
+        }
+        if !self.container_exists(container) {
+            return;
         }
+        eprintln!("Container {} still exists after {} removal attempts", container, REMOVE_ATTEMPTS);
     }
     
-    pub fn run_npm_pack(&self, project_path: &Path) -> Result<String, io::Error> {
-        let absolute_path = project_path.canonicalize()?;
-        
-        let output = Command::new("docker")
-            .args(&[
-                "run",
-                "--rm",
-                "-v", &format!("{}:/build", absolute_path.display()),
-                &self.config.docker_image,
-                "npm", "pack"
-            ])
-            .output()?;
-        
-        if output.status.success() {
-            let filename = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            Ok(filename)
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("npm pack failed: {}", String::from_utf8_lossy(&output.stderr))
-            ))
+    fn container_exists(&self, container: &str) -> bool {
+        Command::new("docker")
+            .args(["container", "inspect", container])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+// How an npm command ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NpmExit {
+    Success,
+    Failure,
+    // Killed after running for this many seconds
+    TimedOut(u64),
+    Cancelled,
+}
+
+pub struct NpmOutput {
+    pub exit: NpmExit,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+// Read a pipe to the end, reporting each line. Invalid UTF-8 is replaced
+// rather than ending the read early.
+fn read_lines<R: Read>(pipe: Option<R>, stream: LogStream, on_line: &(impl Fn(LogStream, &str) + Sync)) -> String {
+    let mut collected = String::new();
+    let Some(pipe) = pipe else {
+        return collected;
+    };
+    let mut reader = BufReader::new(pipe);
+    let mut buffer = Vec::new();
+    while let Ok(read) = reader.read_until(b'\n', &mut buffer) {
+        if read == 0 {
+            break;
         }
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\r', '\n']);
+        on_line(stream, line);
+        collected.push_str(line);
+        collected.push('\n');
+        buffer.clear();
     }
-}
\ No newline at end of file
+    collected
+}
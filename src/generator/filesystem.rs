@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use std::io::Write;
 use uuid::Uuid;
 use crate::generator::builder::GeneratedPackage;
+use crate::generator::framework::Styling;
 use crate::generator::models::CreateComponentRequest;
 
 pub struct PackageBuilder {
@@ -11,26 +12,39 @@ pub struct PackageBuilder {
 }
 
 impl PackageBuilder {
-    pub fn write_rollup_config(&self) -> Result<(), std::io::Error> {
-        let rollup_config = r#"import typescript from '@rollup/plugin-typescript';
+    // With extract_css, stylesheets imported by the sources (CSS Modules)
+    // are bundled into dist/index.css. JavaScript components go through the
+    // TypeScript plugin too, which only picks up .ts/.tsx unless told otherwise.
+    pub fn write_rollup_config(&self, extract_css: bool, typescript: bool) -> Result<(), std::io::Error> {
+        let (css_import, css_plugin) = if extract_css {
+            ("\n    import postcss from 'rollup-plugin-postcss';", ", postcss({ extract: 'index.css' })")
+        } else {
+            ("", "")
+        };
+        let typescript_options = if typescript {
+            ""
+        } else {
+            "{ include: ['src/**/*.ts', 'src/**/*.tsx', 'src/**/*.js', 'src/**/*.jsx'] }"
+        };
+        let rollup_config = format!(r#"import typescript from '@rollup/plugin-typescript';{}
     
-    export default {
+    export default {{
       input: 'src/index.ts',
       output: [
-        {
+        {{
           file: 'dist/index.js',
           format: 'cjs',
           exports: 'named'
-        },
-        {
+        }},
+        {{
           file: 'dist/index.esm.js',
           format: 'es'
-        }
+        }}
       ],
-      external: ['react', 'react-dom'],
-      plugins: [typescript()]
-    };
-    "#;
+      external: ['react', 'react-dom', 'styled-components'],
+      plugins: [typescript({}){}]
+    }};
+    "#, css_import, typescript_options, css_plugin);
         
         let rollup_path = self.base_path.join("rollup.config.js");
         let mut file = fs::File::create(rollup_path)?;
@@ -47,6 +61,7 @@ impl PackageBuilder {
         // Create the base directory structure
         fs::create_dir_all(&base_path)?;
         fs::create_dir_all(base_path.join("src"))?;
+        fs::create_dir_all(base_path.join("styles"))?;
         
         Ok(PackageBuilder {
             job_id,
@@ -56,13 +71,7 @@ impl PackageBuilder {
     
     pub fn write_package(&self, request: &CreateComponentRequest, package: &GeneratedPackage) -> Result<(), std::io::Error> {
         // Write component file
-        let component_filename = if request.typescript {
-            format!("{}.tsx", request.name)
-        } else {
-            format!("{}.jsx", request.name)
-        };
-        
-        let component_path = self.base_path.join("src").join(component_filename);
+        let component_path = self.base_path.join("src").join(&package.component_filename);
         let mut file = fs::File::create(component_path)?;
         file.write_all(package.component_code.as_bytes())?;
         
@@ -71,25 +80,59 @@ impl PackageBuilder {
         let mut file = fs::File::create(index_path)?;
         file.write_all(package.index_ts.as_bytes())?;
         
+        // Write design tokens: the typed module is compiled with the
+        // component, the stylesheets ship as they are
+        let tokens_path = self.base_path.join("src").join("tokens.ts");
+        let mut file = fs::File::create(tokens_path)?;
+        file.write_all(package.tokens.typescript.as_bytes())?;
+
+        let css_path = self.base_path.join("styles").join("tokens.css");
+        let mut file = fs::File::create(css_path)?;
+        file.write_all(package.tokens.css.as_bytes())?;
+
+        let scss_path = self.base_path.join("styles").join("_tokens.scss");
+        let mut file = fs::File::create(scss_path)?;
+        file.write_all(package.tokens.scss.as_bytes())?;
+
+        // Write themes
+        let themes_path = self.base_path.join("src").join("themes.ts");
+        let mut file = fs::File::create(themes_path)?;
+        file.write_all(package.tokens.themes.as_bytes())?;
+
+        // Write framework-specific files
+        for extra in &package.extra_files {
+            let path = self.base_path.join(&extra.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = fs::File::create(path)?;
+            file.write_all(extra.contents.as_bytes())?;
+        }
+        
         // Write package.json
         let package_json_path = self.base_path.join("package.json");
         let mut file = fs::File::create(package_json_path)?;
         file.write_all(package.package_json.as_bytes())?;
         
-        // Write tsconfig.json if TypeScript
-        if request.typescript {
-            let tsconfig = self.create_tsconfig();
-            let tsconfig_path = self.base_path.join("tsconfig.json");
-            let mut file = fs::File::create(tsconfig_path)?;
-            file.write_all(tsconfig.as_bytes())?;
+        // Frameworks other than React ship their own build setup
+        if !package.framework.uses_default_build() {
+            return Ok(());
         }
-        self.write_rollup_config()?;
+
+        // The entry point, tokens and themes are TypeScript either way, so the
+        // tsconfig is always written; JavaScript components are allowed in
+        let tsconfig = self.create_tsconfig(request.typescript);
+        let tsconfig_path = self.base_path.join("tsconfig.json");
+        let mut file = fs::File::create(tsconfig_path)?;
+        file.write_all(tsconfig.as_bytes())?;
+        self.write_rollup_config(package.styling == Styling::CssModules, request.typescript)?;
         Ok(())
     }
     
-    fn create_tsconfig(&self) -> String {
-        r#"{
-  "compilerOptions": {
+    fn create_tsconfig(&self, typescript: bool) -> String {
+        let allow_js = if typescript { "" } else { "\n    \"allowJs\": true," };
+        format!(r#"{{
+  "compilerOptions": {{{}
     "target": "es5",
     "module": "esnext",
     "lib": ["dom", "esnext"],
@@ -100,13 +143,39 @@ impl PackageBuilder {
     "esModuleInterop": true,
     "skipLibCheck": true,
     "forceConsistentCasingInFileNames": true
-  },
+  }},
   "include": ["src"],
   "exclude": ["node_modules", "dist"]
-}"#.to_string()
+}}"#, allow_js)
     }
     
     pub fn get_path(&self) -> &Path {
         &self.base_path
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn javascript_packages_allow_js_in_the_typescript_build() {
+        let base_path = std::env::temp_dir().join(format!("ui-forge-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&base_path).unwrap();
+        let builder = PackageBuilder { job_id: "test".to_string(), base_path: base_path.clone() };
+
+        let tsconfig: serde_json::Value = serde_json::from_str(&builder.create_tsconfig(false)).unwrap();
+        assert_eq!(tsconfig["compilerOptions"]["allowJs"], true);
+        assert_eq!(tsconfig["compilerOptions"]["jsx"], "react");
+        let tsconfig: serde_json::Value = serde_json::from_str(&builder.create_tsconfig(true)).unwrap();
+        assert!(tsconfig["compilerOptions"].get("allowJs").is_none());
+
+        builder.write_rollup_config(false, false).unwrap();
+        let rollup = fs::read_to_string(base_path.join("rollup.config.js")).unwrap();
+        assert!(rollup.contains("typescript({ include: ['src/**/*.ts', 'src/**/*.tsx', 'src/**/*.js', 'src/**/*.jsx'] })"));
+        builder.write_rollup_config(true, true).unwrap();
+        let rollup = fs::read_to_string(base_path.join("rollup.config.js")).unwrap();
+        assert!(rollup.contains("plugins: [typescript(), postcss({ extract: 'index.css' })]"));
+
+        fs::remove_dir_all(base_path).unwrap();
+    }
+}
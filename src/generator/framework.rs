@@ -0,0 +1,115 @@
+use crate::tokens::model::token_segment;
+
+// Output targets for generated packages, from CreateComponentRequest.framework
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+    React,
+    Vue,
+    Svelte,
+    Lit,
+    Angular,
+    ReactNative,
+}
+
+const SUPPORTED: &[Framework] = &[
+    Framework::React,
+    Framework::ReactNative,
+    Framework::Vue,
+    Framework::Svelte,
+    Framework::Lit,
+    Framework::Angular,
+];
+
+impl Framework {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        SUPPORTED
+            .iter()
+            .copied()
+            .find(|framework| framework.as_str() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = SUPPORTED.iter().map(Framework::as_str).collect();
+                format!("Unsupported framework: {}. Supported frameworks: {}", value, names.join(", "))
+            })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Framework::React => "react",
+            Framework::Vue => "vue",
+            Framework::Svelte => "svelte",
+            Framework::Lit => "lit",
+            Framework::Angular => "angular",
+            Framework::ReactNative => "react-native",
+        }
+    }
+
+    // Source file of the component, inside src/
+    pub fn component_filename(&self, name: &str, typescript: bool) -> String {
+        match self {
+            Framework::React | Framework::ReactNative if typescript => format!("{}.tsx", name),
+            Framework::React | Framework::ReactNative => format!("{}.jsx", name),
+            Framework::Vue => format!("{}.vue", name),
+            Framework::Svelte => format!("{}.svelte", name),
+            Framework::Lit if typescript => format!("{}.ts", name),
+            Framework::Lit => format!("{}.js", name),
+            // Angular libraries are always TypeScript
+            Framework::Angular => format!("{}.component.ts", token_segment(name)),
+        }
+    }
+
+    // React packages are built with the rollup config and tsconfig that
+    // PackageBuilder writes; other frameworks bring their own
+    pub fn uses_default_build(&self) -> bool {
+        *self == Framework::React
+    }
+}
+
+// How React components attach their styles, from CreateComponentRequest.styling.
+// The other frameworks always use their own scoped styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Styling {
+    Inline,
+    Tailwind,
+    StyledComponents,
+    CssModules,
+}
+
+const STYLINGS: &[Styling] = &[
+    Styling::Inline,
+    Styling::Tailwind,
+    Styling::StyledComponents,
+    Styling::CssModules,
+];
+
+impl Styling {
+    pub fn parse(value: Option<&str>, framework: Framework) -> Result<Self, String> {
+        let Some(value) = value else {
+            return Ok(Styling::Inline);
+        };
+        let styling = STYLINGS
+            .iter()
+            .copied()
+            .find(|styling| styling.as_str() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = STYLINGS.iter().map(Styling::as_str).collect();
+                format!("Unsupported styling: {}. Supported styling options: {}", value, names.join(", "))
+            })?;
+        if styling != Styling::Inline && framework != Framework::React {
+            return Err(format!(
+                "Styling {} is only supported for framework react, not {}",
+                value,
+                framework.as_str()
+            ));
+        }
+        Ok(styling)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Styling::Inline => "inline",
+            Styling::Tailwind => "tailwind",
+            Styling::StyledComponents => "styled-components",
+            Styling::CssModules => "css-modules",
+        }
+    }
+}
@@ -0,0 +1,824 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+use mongodb::bson::{doc, Document};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use tokio::sync::{broadcast, Notify, Semaphore};
+use crate::db::mongo::MongoDb;
+use crate::generator::config::GeneratorConfig;
+use crate::generator::docker::{DockerBuilder, LogStream, NpmExit, NpmOutput};
+use crate::generator::filesystem::PackageBuilder;
+
+const COLLECTION: &str = "build_jobs";
+
+// Page size of the job history, and the largest a client may ask for
+pub const DEFAULT_HISTORY_LIMIT: i64 = 50;
+pub const MAX_HISTORY_LIMIT: i64 = 200;
+
+// Lines a slow log subscriber may fall behind before it misses some
+const LOG_BUFFER: usize = 1024;
+
+// Where a build job is. Installing, building and packing are the npm steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Installing,
+    Building,
+    Packing,
+    Succeeded,
+    Failed,
+    // The server stopped while the job was queued or running
+    Interrupted,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Installing => "installing",
+            JobStatus::Building => "building",
+            JobStatus::Packing => "packing",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+            JobStatus::Interrupted => "interrupted",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    // Statuses a job can only have while the server that queued it runs
+    const IN_FLIGHT: [JobStatus; 4] = [
+        JobStatus::Queued,
+        JobStatus::Installing,
+        JobStatus::Building,
+        JobStatus::Packing,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub status: JobStatus,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub step: JobStatus,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub started_at: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+    pub success: Option<bool>,
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+}
+
+// Digests of the tarball written by npm pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checksums {
+    pub sha256: String,
+    pub sha512: String,
+}
+
+// Documents stored in the build_jobs collection, one per build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildJob {
+    pub job_id: String,
+    pub package_name: String,
+    // The create-component request that produced the package
+    pub request: Document,
+    pub status: JobStatus,
+    pub transitions: Vec<StatusChange>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: DateTime<Utc>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub steps: Vec<StepRecord>,
+    // Tarball written by npm pack, relative to the job directory
+    pub artifact: Option<String>,
+    pub checksums: Option<Checksums>,
+    pub error: Option<String>,
+}
+
+impl BuildJob {
+    // Output of the finished steps, stdout before stderr within a step
+    fn log_lines(&self) -> Vec<LogLine> {
+        let mut lines = Vec::new();
+        for record in &self.steps {
+            for (stream, output) in [(LogStream::Stdout, &record.stdout), (LogStream::Stderr, &record.stderr)] {
+                lines.extend(output.lines().map(|line| LogLine {
+                    step: record.step,
+                    stream,
+                    line: line.to_string(),
+                }));
+            }
+        }
+        lines
+    }
+
+    fn set_status(&mut self, status: JobStatus) {
+        self.status = status;
+        self.transitions.push(StatusChange { status, at: Utc::now() });
+    }
+}
+
+// One line printed by an npm step
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLine {
+    pub step: JobStatus,
+    pub stream: LogStream,
+    pub line: String,
+}
+
+// A job that has not finished: everything it printed so far, the channel
+// new lines are sent on and its cancellation. Dropping it ends the log
+// subscriptions.
+struct ActiveJob {
+    lines: Vec<LogLine>,
+    sender: broadcast::Sender<LogLine>,
+    cancellation: Arc<Cancellation>,
+}
+
+// Set by DELETE /jobs/{job_id}. Running steps check the flag; the notify
+// wakes a job that is still waiting for a worker.
+#[derive(Default)]
+struct Cancellation {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+impl Cancellation {
+    fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+        self.notify.notify_one();
+    }
+
+    fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
+
+pub enum CancelOutcome {
+    // The job will stop; it is returned as it was when asked
+    Requested(BuildJob),
+    // The job had already ended
+    Finished(BuildJob),
+    NotFound,
+}
+
+#[derive(Debug, PartialEq)]
+enum StepOutcome {
+    // stdout of the step
+    Done(String),
+    Failed(String),
+    // The container was killed when the build timeout passed
+    TimedOut(String),
+    Cancelled,
+}
+
+// What a log subscriber gets: earlier lines, then new ones while the job runs
+pub struct LogFollow {
+    pub backlog: Vec<LogLine>,
+    pub live: Option<broadcast::Receiver<LogLine>>,
+}
+
+// Builds waiting for or running on a bounded number of workers. Every
+// change is written to MongoDB; the map holds the unfinished jobs of this
+// process so polling them does not hit the database.
+#[derive(Clone)]
+pub struct JobQueue {
+    db: MongoDb,
+    jobs: Arc<Mutex<HashMap<String, BuildJob>>>,
+    active: Arc<Mutex<HashMap<String, ActiveJob>>>,
+    workers: Arc<Semaphore>,
+}
+
+impl JobQueue {
+    pub fn new(db: MongoDb, workers: usize) -> Self {
+        JobQueue {
+            db,
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            active: Arc::new(Mutex::new(HashMap::new())),
+            workers: Arc::new(Semaphore::new(workers.max(1))),
+        }
+    }
+
+    pub fn from_env(db: MongoDb) -> Self {
+        Self::new(db, GeneratorConfig::from_env().build_workers)
+    }
+
+    // Jobs left queued or running by a previous process never finish, and
+    // their workspace may be half built, so they are marked interrupted
+    pub async fn mark_interrupted(&self) -> Result<u64, mongodb::error::Error> {
+        let in_flight: Vec<&str> = JobStatus::IN_FLIGHT.iter().map(JobStatus::as_str).collect();
+        let now = mongodb::bson::DateTime::now();
+        let filter = doc! { "status": { "$in": in_flight } };
+        let update = doc! {
+            "$set": {
+                "status": JobStatus::Interrupted.as_str(),
+                "finished_at": now,
+                "error": "The server restarted before the build finished",
+            },
+            "$push": {
+                "transitions": { "status": JobStatus::Interrupted.as_str(), "at": now },
+            },
+        };
+        self.db.update_in_collection(COLLECTION, filter, update).await
+    }
+
+    // Queue the npm steps for a package already written to disk
+    pub async fn enqueue<R: Serialize>(&self, builder: PackageBuilder, package_name: &str, request: &R) -> String {
+        let job_id = builder.job_id.clone();
+        let request = mongodb::bson::to_document(request).unwrap_or_else(|e| {
+            eprintln!("Failed to store the request of build job {}: {}", job_id, e);
+            Document::new()
+        });
+        let mut job = BuildJob {
+            job_id: job_id.clone(),
+            package_name: package_name.to_string(),
+            request,
+            status: JobStatus::Queued,
+            transitions: Vec::new(),
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            steps: Vec::new(),
+            artifact: None,
+            checksums: None,
+            error: None,
+        };
+        job.set_status(JobStatus::Queued);
+        self.jobs.lock().unwrap().insert(job_id.clone(), job.clone());
+        let cancellation = Arc::new(Cancellation::default());
+        self.active.lock().unwrap().insert(
+            job_id.clone(),
+            ActiveJob {
+                lines: Vec::new(),
+                sender: broadcast::channel(LOG_BUFFER).0,
+                cancellation: cancellation.clone(),
+            },
+        );
+        self.save(&job).await;
+
+        let queue = self.clone();
+        actix_web::rt::spawn(async move {
+            tokio::select! {
+                permit = queue.workers.clone().acquire_owned() => {
+                    let Ok(_permit) = permit else {
+                        return;
+                    };
+                    queue.run(&builder, &cancellation).await;
+                }
+                _ = cancellation.notify.notified() => queue.abandon(&builder, JobStatus::Cancelled, None).await,
+            }
+        });
+
+        job_id
+    }
+
+    // An unfinished job of this process, or one from the stored history
+    pub async fn get(&self, job_id: &str) -> Result<Option<BuildJob>, mongodb::error::Error> {
+        if let Some(job) = self.jobs.lock().unwrap().get(job_id).cloned() {
+            return Ok(Some(job));
+        }
+        self.db
+            .get_document_from_collection(COLLECTION, doc! { "job_id": job_id })
+            .await
+    }
+
+    // Output of a job. The backlog and the subscription are taken under the
+    // same lock, so no line is missed or repeated.
+    pub async fn follow_logs(&self, job_id: &str) -> Result<Option<LogFollow>, mongodb::error::Error> {
+        if let Some(active) = self.active.lock().unwrap().get(job_id) {
+            return Ok(Some(LogFollow {
+                backlog: active.lines.clone(),
+                live: Some(active.sender.subscribe()),
+            }));
+        }
+        Ok(self.get(job_id).await?.map(|job| LogFollow {
+            backlog: job.log_lines(),
+            live: None,
+        }))
+    }
+
+    // Stop a queued or running job. It ends as cancelled once its container
+    // is killed, and its workspace is removed.
+    pub async fn cancel(&self, job_id: &str) -> Result<CancelOutcome, mongodb::error::Error> {
+        let requested = match self.active.lock().unwrap().get(job_id) {
+            Some(active) => {
+                active.cancellation.request();
+                true
+            }
+            None => false,
+        };
+        Ok(match self.get(job_id).await? {
+            Some(job) if requested => CancelOutcome::Requested(job),
+            Some(job) => CancelOutcome::Finished(job),
+            None => CancelOutcome::NotFound,
+        })
+    }
+
+    // A page of stored jobs, newest first, optionally for one package. Step
+    // output is left out; it is in the single job and its logs.
+    pub async fn history(
+        &self,
+        package_name: Option<&str>,
+        skip: u64,
+        limit: i64,
+    ) -> Result<Vec<BuildJob>, mongodb::error::Error> {
+        let filter = match package_name {
+            Some(package_name) => doc! { "package_name": package_name },
+            None => doc! {},
+        };
+        self.db
+            .get_page_from_collection(
+                COLLECTION,
+                filter,
+                doc! { "created_at": -1 },
+                doc! { "steps.stdout": 0, "steps.stderr": 0 },
+                skip,
+                limit,
+            )
+            .await
+    }
+
+    // Saves filter on job_id, and it identifies a job
+    pub async fn create_indexes(&self) -> Result<(), mongodb::error::Error> {
+        self.db.create_unique_index(COLLECTION, doc! { "job_id": 1 }).await
+    }
+
+    // Run npm install / build / pack in Docker, recording each step
+    async fn run(&self, builder: &PackageBuilder, cancellation: &Arc<Cancellation>) {
+        let job_id = &builder.job_id;
+        if cancellation.is_requested() {
+            self.abandon(builder, JobStatus::Cancelled, None).await;
+            return;
+        }
+        self.update(job_id, |job| job.started_at = Some(Utc::now())).await;
+
+        if let Err(e) = blocking(|docker| docker.check_docker()).await {
+            let message = format!(
+                "Docker not available: {}. Ensure Docker is running, then call GET /api/generator/build-docker-image",
+                e
+            );
+            self.finish(job_id, JobStatus::Failed, Some(message)).await;
+            return;
+        }
+
+        let steps = [
+            (JobStatus::Installing, ["install"].as_slice()),
+            (JobStatus::Building, ["run", "build"].as_slice()),
+            (JobStatus::Packing, ["pack"].as_slice()),
+        ];
+        let mut packed = String::new();
+        for (status, args) in steps {
+            if cancellation.is_requested() {
+                self.abandon(builder, JobStatus::Cancelled, None).await;
+                return;
+            }
+            match self.step(builder, status, args, cancellation).await {
+                StepOutcome::Done(stdout) => packed = stdout,
+                StepOutcome::Failed(e) => {
+                    self.finish(job_id, JobStatus::Failed, Some(e)).await;
+                    return;
+                }
+                StepOutcome::TimedOut(e) => {
+                    self.abandon(builder, JobStatus::Failed, Some(e)).await;
+                    return;
+                }
+                StepOutcome::Cancelled => {
+                    self.abandon(builder, JobStatus::Cancelled, None).await;
+                    return;
+                }
+            }
+        }
+
+        // npm pack prints the tarball name last
+        let filename = packed
+            .lines()
+            .map(str::trim)
+            .rfind(|line| !line.is_empty())
+            .unwrap_or_default()
+            .to_string();
+        let checksums = match std::fs::read(builder.get_path().join(&filename)) {
+            Ok(tarball) => Some(Checksums {
+                sha256: hex::encode(Sha256::digest(&tarball)),
+                sha512: hex::encode(Sha512::digest(&tarball)),
+            }),
+            Err(e) => {
+                eprintln!("Failed to read package {} of build job {}: {}", filename, job_id, e);
+                None
+            }
+        };
+        println!("Package ready: {:?}/{}", builder.get_path(), filename);
+        self.update(job_id, |job| {
+            job.artifact = Some(filename);
+            job.checksums = checksums;
+        })
+        .await;
+        self.finish(job_id, JobStatus::Succeeded, None).await;
+    }
+
+    // Run one npm command in a container named after the job and step
+    async fn step(
+        &self,
+        builder: &PackageBuilder,
+        status: JobStatus,
+        args: &'static [&'static str],
+        cancellation: &Arc<Cancellation>,
+    ) -> StepOutcome {
+        let job_id = &builder.job_id;
+        self.update(job_id, |job| {
+            job.set_status(status);
+            job.steps.push(StepRecord {
+                step: status,
+                started_at: Utc::now(),
+                finished_at: None,
+                duration_ms: None,
+                success: None,
+                stdout: String::new(),
+                stderr: String::new(),
+            });
+        })
+        .await;
+
+        let started = Instant::now();
+        let path = builder.get_path().to_path_buf();
+        let container = format!("ui-forge-{}-{}", job_id, status.as_str());
+        let queue = self.clone();
+        let id = job_id.clone();
+        let cancellation = cancellation.clone();
+        let result = blocking(move |docker| {
+            docker.run_npm(&path, &container, args, &cancellation.requested, |stream, line| {
+                queue.publish(&id, LogLine { step: status, stream, line: line.to_string() })
+            })
+        })
+        .await;
+        let elapsed = started.elapsed().as_millis() as u64;
+
+        let command = format!("npm {}", args.join(" "));
+        let (output, outcome) = match result {
+            Ok(output) => {
+                let outcome = step_outcome(&command, &output);
+                (output, outcome)
+            }
+            Err(e) => {
+                let output = NpmOutput { exit: NpmExit::Failure, stdout: String::new(), stderr: String::new() };
+                (output, StepOutcome::Failed(format!("{} could not be started: {}", command, e)))
+            }
+        };
+
+        self.update(job_id, |job| {
+            if let Some(record) = job.steps.last_mut() {
+                record.finished_at = Some(Utc::now());
+                record.duration_ms = Some(elapsed);
+                record.success = Some(matches!(outcome, StepOutcome::Done(_)));
+                record.stdout = output.stdout;
+                record.stderr = output.stderr;
+            }
+        })
+        .await;
+        outcome
+    }
+
+    async fn finish(&self, job_id: &str, status: JobStatus, error: Option<String>) {
+        self.update(job_id, |job| {
+            job.set_status(status);
+            job.finished_at = Some(Utc::now());
+            job.error = error;
+        })
+        .await;
+        // Finished jobs are read back from MongoDB
+        self.jobs.lock().unwrap().remove(job_id);
+        self.active.lock().unwrap().remove(job_id);
+        println!("Build job {} {}", job_id, status.as_str());
+    }
+
+    // A cancelled or timed-out job leaves nothing behind on disk
+    async fn abandon(&self, builder: &PackageBuilder, status: JobStatus, error: Option<String>) {
+        self.finish(&builder.job_id, status, error).await;
+        if let Err(e) = std::fs::remove_dir_all(builder.get_path()) {
+            eprintln!("Failed to remove workspace of build job {}: {}", builder.job_id, e);
+        }
+    }
+
+    fn publish(&self, job_id: &str, line: LogLine) {
+        if let Some(active) = self.active.lock().unwrap().get_mut(job_id) {
+            // Sending only fails when nobody is subscribed
+            let _ = active.sender.send(line.clone());
+            active.lines.push(line);
+        }
+    }
+
+    async fn update<F: FnOnce(&mut BuildJob)>(&self, job_id: &str, apply: F) {
+        let job = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(job) = jobs.get_mut(job_id) else {
+                return;
+            };
+            apply(job);
+            job.clone()
+        };
+        self.save(&job).await;
+    }
+
+    async fn save(&self, job: &BuildJob) {
+        if let Err(e) = self
+            .db
+            .replace_in_collection(COLLECTION, doc! { "job_id": &job.job_id }, job)
+            .await
+        {
+            eprintln!("Failed to store build job {} in MongoDB: {}", job.job_id, e);
+        }
+    }
+}
+
+fn step_outcome(command: &str, output: &NpmOutput) -> StepOutcome {
+    match output.exit {
+        NpmExit::Success => StepOutcome::Done(output.stdout.clone()),
+        NpmExit::Failure => StepOutcome::Failed(format!("{} failed: {}", command, output.stderr)),
+        NpmExit::TimedOut(seconds) => {
+            StepOutcome::TimedOut(format!("{} timed out after {} seconds", command, seconds))
+        }
+        NpmExit::Cancelled => StepOutcome::Cancelled,
+    }
+}
+
+// Docker commands block, so they run on the blocking thread pool
+async fn blocking<T, F>(run: F) -> Result<T, io::Error>
+where
+    F: FnOnce(&DockerBuilder) -> Result<T, io::Error> + Send + 'static,
+    T: Send + 'static,
+{
+    actix_web::rt::task::spawn_blocking(move || run(&DockerBuilder::new()))
+        .await
+        .map_err(io::Error::other)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    // Saves fail fast and are only logged
+    async fn queue() -> JobQueue {
+        JobQueue::new(MongoDb::unreachable().await, 1)
+    }
+
+    fn workspace() -> PackageBuilder {
+        let job_id = Uuid::new_v4().to_string();
+        let base_path = std::env::temp_dir().join(format!("ui-forge-test-{}", job_id));
+        std::fs::create_dir_all(&base_path).unwrap();
+        std::fs::write(base_path.join("package.json"), "{}").unwrap();
+        PackageBuilder { job_id, base_path }
+    }
+
+    // Queue a job that no worker picks up while the returned permit is held
+    async fn queued(queue: &JobQueue) -> (PackageBuilder, tokio::sync::OwnedSemaphorePermit) {
+        let permit = queue.workers.clone().acquire_owned().await.unwrap();
+        let builder = workspace();
+        let copy = PackageBuilder { job_id: builder.job_id.clone(), base_path: builder.base_path.clone() };
+        queue.enqueue(builder, "test-package", &serde_json::json!({})).await;
+        (copy, permit)
+    }
+
+    #[actix_web::test]
+    async fn queued_jobs_wait_for_a_free_worker() {
+        let queue = queue().await;
+        let (first, _permit) = queued(&queue).await;
+        let second = workspace();
+        let second_path = second.base_path.clone();
+        let second_id = queue.enqueue(second, "other-package", &serde_json::json!({ "name": "Other" })).await;
+
+        for job_id in [&first.job_id, &second_id] {
+            let job = queue.get(job_id).await.unwrap().unwrap();
+            assert_eq!(job.status, JobStatus::Queued);
+            let statuses: Vec<JobStatus> = job.transitions.iter().map(|change| change.status).collect();
+            assert_eq!(statuses, [JobStatus::Queued]);
+            assert!(job.started_at.is_none() && job.finished_at.is_none());
+            assert!(job.steps.is_empty());
+        }
+        let second = queue.get(&second_id).await.unwrap().unwrap();
+        assert_eq!(second.package_name, "other-package");
+        assert_eq!(second.request.get_str("name").unwrap(), "Other");
+        std::fs::remove_dir_all(first.get_path()).unwrap();
+        std::fs::remove_dir_all(second_path).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn cancelling_one_queued_job_leaves_the_others_queued() {
+        let queue = queue().await;
+        let (first, _permit) = queued(&queue).await;
+        let second = workspace();
+        let second_id = second.job_id.clone();
+        let second_path = second.base_path.clone();
+        queue.enqueue(second, "test-package", &serde_json::json!({})).await;
+
+        queue.cancel(&first.job_id).await.unwrap();
+        for _ in 0..200 {
+            if !queue.jobs.lock().unwrap().contains_key(&first.job_id) {
+                break;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!queue.jobs.lock().unwrap().contains_key(&first.job_id));
+        assert_eq!(queue.get(&second_id).await.unwrap().unwrap().status, JobStatus::Queued);
+        std::fs::remove_dir_all(second_path).unwrap();
+    }
+
+    #[test]
+    fn statuses_are_stored_in_lowercase() {
+        for status in JobStatus::IN_FLIGHT.into_iter().chain([
+            JobStatus::Succeeded,
+            JobStatus::Failed,
+            JobStatus::Interrupted,
+            JobStatus::Cancelled,
+        ]) {
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+    }
+
+    fn finished_job() -> BuildJob {
+        let mut job = BuildJob {
+            job_id: "job-1".to_string(),
+            package_name: "@acme/button".to_string(),
+            request: doc! { "name": "Button" },
+            status: JobStatus::Queued,
+            transitions: Vec::new(),
+            created_at: Utc::now(),
+            started_at: Some(Utc::now()),
+            finished_at: None,
+            steps: vec![StepRecord {
+                step: JobStatus::Installing,
+                started_at: Utc::now(),
+                finished_at: Some(Utc::now()),
+                duration_ms: Some(1200),
+                success: Some(false),
+                stdout: "added 12 packages\n".to_string(),
+                stderr: "npm WARN deprecated\nnpm ERR! missing script\n".to_string(),
+            }],
+            artifact: None,
+            checksums: None,
+            error: Some("npm install failed".to_string()),
+        };
+        job.set_status(JobStatus::Queued);
+        job.set_status(JobStatus::Installing);
+        job.set_status(JobStatus::Failed);
+        job.finished_at = Some(Utc::now());
+        job
+    }
+
+    #[test]
+    fn jobs_are_stored_as_documents_with_dates_and_round_trip() {
+        let job = finished_job();
+        let document = mongodb::bson::to_document(&job).unwrap();
+        assert_eq!(document.get_str("status").unwrap(), "failed");
+        assert!(document.get_datetime("created_at").is_ok());
+        assert!(document.get_datetime("finished_at").is_ok());
+        let transitions = document.get_array("transitions").unwrap();
+        assert_eq!(transitions.len(), 3);
+        assert!(transitions[0].as_document().unwrap().get_datetime("at").is_ok());
+
+        let stored: BuildJob = mongodb::bson::from_document(document).unwrap();
+        assert_eq!(stored.status, JobStatus::Failed);
+        assert_eq!(stored.error.as_deref(), Some("npm install failed"));
+        assert_eq!(stored.steps[0].duration_ms, Some(1200));
+        assert_eq!(
+            stored.finished_at.unwrap().timestamp_millis(),
+            job.finished_at.unwrap().timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn history_documents_without_step_output_still_load() {
+        // History pages project the step output away
+        let mut document = mongodb::bson::to_document(&finished_job()).unwrap();
+        let steps = vec![mongodb::bson::Bson::Document(doc! {
+            "step": "installing",
+            "started_at": mongodb::bson::DateTime::now(),
+            "duration_ms": 1200_i64,
+            "success": false,
+        })];
+        document.insert("steps", steps);
+        let stored: BuildJob = mongodb::bson::from_document(document).unwrap();
+        assert!(stored.steps[0].stdout.is_empty());
+        assert!(stored.steps[0].finished_at.is_none());
+    }
+
+    #[test]
+    fn only_unfinished_statuses_are_marked_interrupted() {
+        let in_flight: Vec<&str> = JobStatus::IN_FLIGHT.iter().map(JobStatus::as_str).collect();
+        assert_eq!(in_flight, ["queued", "installing", "building", "packing"]);
+    }
+
+    #[test]
+    fn finished_jobs_replay_stdout_before_stderr_per_step() {
+        let lines: Vec<(LogStream, String)> = finished_job()
+            .log_lines()
+            .into_iter()
+            .map(|line| (line.stream, line.line))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (LogStream::Stdout, "added 12 packages".to_string()),
+                (LogStream::Stderr, "npm WARN deprecated".to_string()),
+                (LogStream::Stderr, "npm ERR! missing script".to_string()),
+            ]
+        );
+    }
+
+    #[actix_web::test]
+    async fn followers_get_the_backlog_then_live_lines() {
+        let queue = queue().await;
+        let (builder, _permit) = queued(&queue).await;
+        let line = |text: &str| LogLine { step: JobStatus::Installing, stream: LogStream::Stdout, line: text.to_string() };
+
+        queue.publish(&builder.job_id, line("first"));
+        let mut early = queue.follow_logs(&builder.job_id).await.unwrap().unwrap();
+        queue.publish(&builder.job_id, line("second"));
+        let late = queue.follow_logs(&builder.job_id).await.unwrap().unwrap();
+
+        let backlog = |follow: &LogFollow| follow.backlog.iter().map(|line| line.line.clone()).collect::<Vec<_>>();
+        assert_eq!(backlog(&early), ["first"]);
+        assert_eq!(backlog(&late), ["first", "second"]);
+        let mut live = early.live.take().unwrap();
+        assert_eq!(live.recv().await.unwrap().line, "second");
+
+        // Lines published after the job finished go nowhere
+        queue.abandon(&builder, JobStatus::Cancelled, None).await;
+        queue.publish(&builder.job_id, line("third"));
+        assert!(live.recv().await.is_err());
+    }
+
+    #[test]
+    fn a_timed_out_step_is_told_apart_from_a_failed_one() {
+        let output = |exit| NpmOutput { exit, stdout: "pkg-1.0.0.tgz\n".to_string(), stderr: "ERR!".to_string() };
+        assert_eq!(
+            step_outcome("npm install", &output(NpmExit::TimedOut(30))),
+            StepOutcome::TimedOut("npm install timed out after 30 seconds".to_string())
+        );
+        assert_eq!(
+            step_outcome("npm run build", &output(NpmExit::Failure)),
+            StepOutcome::Failed("npm run build failed: ERR!".to_string())
+        );
+        assert_eq!(step_outcome("npm pack", &output(NpmExit::Cancelled)), StepOutcome::Cancelled);
+        assert_eq!(
+            step_outcome("npm pack", &output(NpmExit::Success)),
+            StepOutcome::Done("pkg-1.0.0.tgz\n".to_string())
+        );
+    }
+
+    #[actix_web::test]
+    async fn cancelling_a_queued_job_removes_its_workspace() {
+        let queue = queue().await;
+        let (builder, _permit) = queued(&queue).await;
+        assert_eq!(queue.get(&builder.job_id).await.unwrap().unwrap().status, JobStatus::Queued);
+
+        assert!(matches!(queue.cancel(&builder.job_id).await.unwrap(), CancelOutcome::Requested(_)));
+        for _ in 0..200 {
+            if !builder.get_path().exists() {
+                break;
+            }
+            actix_web::rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!builder.get_path().exists());
+        assert!(queue.jobs.lock().unwrap().is_empty());
+        assert!(queue.active.lock().unwrap().is_empty());
+    }
+
+    #[actix_web::test]
+    async fn a_timed_out_job_fails_and_removes_its_workspace() {
+        let queue = queue().await;
+        let (builder, _permit) = queued(&queue).await;
+        let mut follow = queue.follow_logs(&builder.job_id).await.unwrap().unwrap();
+
+        let error = "npm install timed out after 30 seconds".to_string();
+        queue.abandon(&builder, JobStatus::Failed, Some(error)).await;
+        assert!(!builder.get_path().exists());
+        assert!(queue.jobs.lock().unwrap().is_empty());
+        // The log subscription ends with the job
+        assert!(follow.live.take().unwrap().recv().await.is_err());
+    }
+}
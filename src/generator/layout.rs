@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use crate::figma::models::{LayoutMode, Node};
+use crate::generator::node_tree::px;
+
+// Children with layoutPositioning ABSOLUTE opt out of their parent's flow
+pub fn is_absolute(node: &Node) -> bool {
+    node.layout_positioning.as_deref() == Some("ABSOLUTE")
+}
+
+fn is_horizontal(node: &Node) -> bool {
+    node.layout_mode == Some(LayoutMode::Horizontal)
+}
+
+// Translate an auto-layout frame into flexbox container properties
+pub fn apply_auto_layout(node: &Node, styles: &mut BTreeMap<String, String>) {
+    if !node.is_auto_layout() {
+        return;
+    }
+    let horizontal = is_horizontal(node);
+
+    styles.insert("display".to_string(), "flex".to_string());
+    styles.insert(
+        "flexDirection".to_string(),
+        if horizontal { "row" } else { "column" }.to_string(),
+    );
+    styles.insert("boxSizing".to_string(), "border-box".to_string());
+
+    // Spacing between items; wrapping rows use counterAxisSpacing between lines
+    let item_spacing = node.item_spacing.unwrap_or(0.0);
+    let wraps = node.layout_wrap.as_deref() == Some("WRAP");
+    let space_between = node.primary_axis_align_items.as_deref() == Some("SPACE_BETWEEN");
+    if wraps {
+        styles.insert("flexWrap".to_string(), "wrap".to_string());
+        let counter_spacing = node.counter_axis_spacing.unwrap_or(item_spacing);
+        let (row_gap, column_gap) = if horizontal {
+            (counter_spacing, item_spacing)
+        } else {
+            (item_spacing, counter_spacing)
+        };
+        if row_gap != 0.0 {
+            styles.insert("rowGap".to_string(), px(row_gap));
+        }
+        if column_gap != 0.0 && !space_between {
+            styles.insert("columnGap".to_string(), px(column_gap));
+        }
+    } else if item_spacing != 0.0 && !space_between {
+        styles.insert("gap".to_string(), px(item_spacing));
+    }
+
+    for (value, css_key) in [
+        (node.padding_top, "paddingTop"),
+        (node.padding_right, "paddingRight"),
+        (node.padding_bottom, "paddingBottom"),
+        (node.padding_left, "paddingLeft"),
+    ] {
+        if let Some(value) = value
+            && value != 0.0
+        {
+            styles.insert(css_key.to_string(), px(value));
+        }
+    }
+
+    let justify = match node.primary_axis_align_items.as_deref() {
+        Some("CENTER") => "center",
+        Some("MAX") => "flex-end",
+        Some("SPACE_BETWEEN") => "space-between",
+        _ => "flex-start",
+    };
+    styles.insert("justifyContent".to_string(), justify.to_string());
+
+    let align = match node.counter_axis_align_items.as_deref() {
+        Some("CENTER") => "center",
+        Some("MAX") => "flex-end",
+        Some("BASELINE") => "baseline",
+        _ => "flex-start",
+    };
+    styles.insert("alignItems".to_string(), align.to_string());
+
+    if wraps && node.counter_axis_align_content.as_deref() == Some("SPACE_BETWEEN") {
+        styles.insert("alignContent".to_string(), "space-between".to_string());
+    }
+
+    // Hug contents: let the content size the frame instead of the bounding box
+    let (primary_dimension, counter_dimension) = dimensions(horizontal);
+    if node.primary_axis_sizing_mode.as_deref() == Some("AUTO") {
+        styles.remove(primary_dimension);
+    }
+    if node.counter_axis_sizing_mode.as_deref() == Some("AUTO") {
+        styles.remove(counter_dimension);
+    }
+}
+
+// Flex item properties for a child placed inside an auto-layout parent
+pub fn apply_child_layout(node: &Node, parent: &Node, styles: &mut BTreeMap<String, String>) {
+    let (primary_dimension, counter_dimension) = dimensions(is_horizontal(parent));
+
+    if node.layout_grow.unwrap_or(0.0) > 0.0 {
+        // Fill container along the primary axis
+        styles.insert("flexGrow".to_string(), "1".to_string());
+        styles.insert("flexBasis".to_string(), "0".to_string());
+        styles.remove(primary_dimension);
+    } else {
+        // Figma never shrinks fixed children
+        styles.insert("flexShrink".to_string(), "0".to_string());
+    }
+
+    if node.layout_align.as_deref() == Some("STRETCH") {
+        styles.insert("alignSelf".to_string(), "stretch".to_string());
+        styles.remove(counter_dimension);
+    }
+}
+
+// (primary, counter) CSS dimension for a layout direction
+fn dimensions(horizontal: bool) -> (&'static str, &'static str) {
+    if horizontal {
+        ("width", "height")
+    } else {
+        ("height", "width")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(properties: serde_json::Value) -> Node {
+        let mut value = json!({ "id": "1:1", "type": "FRAME" });
+        value.as_object_mut().unwrap().extend(properties.as_object().unwrap().clone());
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn container(properties: serde_json::Value) -> BTreeMap<String, String> {
+        let mut styles = BTreeMap::from([
+            ("width".to_string(), "200px".to_string()),
+            ("height".to_string(), "40px".to_string()),
+        ]);
+        apply_auto_layout(&node(properties), &mut styles);
+        styles
+    }
+
+    fn child(properties: serde_json::Value, parent: serde_json::Value) -> BTreeMap<String, String> {
+        let mut styles = BTreeMap::from([
+            ("width".to_string(), "80px".to_string()),
+            ("height".to_string(), "20px".to_string()),
+        ]);
+        apply_child_layout(&node(properties), &node(parent), &mut styles);
+        styles
+    }
+
+    #[test]
+    fn frames_without_auto_layout_are_left_alone() {
+        let styles = container(json!({ "layoutMode": "NONE", "itemSpacing": 8.0 }));
+        assert_eq!(styles.len(), 2);
+        assert!(!styles.contains_key("display"));
+    }
+
+    #[test]
+    fn horizontal_frames_become_flex_rows() {
+        let styles = container(json!({
+            "layoutMode": "HORIZONTAL",
+            "primaryAxisAlignItems": "CENTER",
+            "counterAxisAlignItems": "MAX",
+            "itemSpacing": 8.0,
+            "paddingTop": 4.0,
+            "paddingRight": 12.5,
+            "paddingBottom": 0.0,
+            "paddingLeft": 12.0,
+        }));
+        assert_eq!(styles["display"], "flex");
+        assert_eq!(styles["flexDirection"], "row");
+        assert_eq!(styles["justifyContent"], "center");
+        assert_eq!(styles["alignItems"], "flex-end");
+        assert_eq!(styles["gap"], "8px");
+        assert_eq!(styles["paddingTop"], "4px");
+        assert_eq!(styles["paddingRight"], "12.5px");
+        assert_eq!(styles["paddingLeft"], "12px");
+        assert!(!styles.contains_key("paddingBottom"));
+    }
+
+    #[test]
+    fn vertical_frames_become_flex_columns() {
+        let styles = container(json!({
+            "layoutMode": "VERTICAL",
+            "counterAxisAlignItems": "BASELINE",
+        }));
+        assert_eq!(styles["flexDirection"], "column");
+        assert_eq!(styles["justifyContent"], "flex-start");
+        assert_eq!(styles["alignItems"], "baseline");
+        assert!(!styles.contains_key("gap"));
+    }
+
+    #[test]
+    fn space_between_replaces_the_gap() {
+        let styles = container(json!({
+            "layoutMode": "HORIZONTAL",
+            "primaryAxisAlignItems": "SPACE_BETWEEN",
+            "itemSpacing": 8.0,
+        }));
+        assert_eq!(styles["justifyContent"], "space-between");
+        assert!(!styles.contains_key("gap"));
+    }
+
+    #[test]
+    fn wrapping_rows_use_the_counter_axis_spacing_between_lines() {
+        let styles = container(json!({
+            "layoutMode": "HORIZONTAL",
+            "layoutWrap": "WRAP",
+            "itemSpacing": 8.0,
+            "counterAxisSpacing": 16.0,
+            "counterAxisAlignContent": "SPACE_BETWEEN",
+        }));
+        assert_eq!(styles["flexWrap"], "wrap");
+        assert_eq!(styles["columnGap"], "8px");
+        assert_eq!(styles["rowGap"], "16px");
+        assert_eq!(styles["alignContent"], "space-between");
+        assert!(!styles.contains_key("gap"));
+
+        let styles = container(json!({
+            "layoutMode": "VERTICAL",
+            "layoutWrap": "WRAP",
+            "itemSpacing": 8.0,
+        }));
+        assert_eq!(styles["rowGap"], "8px");
+        assert_eq!(styles["columnGap"], "8px");
+        assert!(!styles.contains_key("alignContent"));
+    }
+
+    #[test]
+    fn hugging_frames_drop_their_fixed_size() {
+        let styles = container(json!({
+            "layoutMode": "VERTICAL",
+            "primaryAxisSizingMode": "AUTO",
+            "counterAxisSizingMode": "FIXED",
+        }));
+        assert!(!styles.contains_key("height"));
+        assert_eq!(styles["width"], "200px");
+    }
+
+    #[test]
+    fn growing_children_fill_the_primary_axis() {
+        let styles = child(json!({ "layoutGrow": 1.0 }), json!({ "layoutMode": "HORIZONTAL" }));
+        assert_eq!(styles["flexGrow"], "1");
+        assert_eq!(styles["flexBasis"], "0");
+        assert!(!styles.contains_key("width"));
+        assert!(!styles.contains_key("flexShrink"));
+
+        let styles = child(json!({ "layoutGrow": 0.0 }), json!({ "layoutMode": "HORIZONTAL" }));
+        assert_eq!(styles["flexShrink"], "0");
+        assert_eq!(styles["width"], "80px");
+    }
+
+    #[test]
+    fn stretched_children_fill_the_counter_axis() {
+        let styles = child(json!({ "layoutAlign": "STRETCH" }), json!({ "layoutMode": "VERTICAL" }));
+        assert_eq!(styles["alignSelf"], "stretch");
+        assert!(!styles.contains_key("width"));
+        assert_eq!(styles["height"], "20px");
+    }
+
+    #[test]
+    fn absolute_children_opt_out_of_the_flow() {
+        assert!(is_absolute(&node(json!({ "layoutPositioning": "ABSOLUTE" }))));
+        assert!(!is_absolute(&node(json!({ "layoutPositioning": "AUTO" }))));
+    }
+}
@@ -0,0 +1,239 @@
+// Lit output: a custom element with reactive properties, shadow-DOM styles
+// and a default slot for children, compiled with tsc
+use tera::{Context, Tera};
+use crate::generator::builder::{GeneratedPackage, GeneratedFile};
+use crate::generator::framework::{Framework, Styling};
+use crate::generator::markup::{class_names, render_stylesheet, render_template, tag_name, Dialect};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::ElementNode;
+use crate::tokens::css::render_files;
+use crate::tokens::model::TokenSet;
+
+pub fn add_templates(tera: &mut Tera) {
+    tera.add_raw_template("lit_component", r##"import { LitElement, css, html, nothing } from 'lit';
+{%- if typescript %}
+import { customElement, property } from 'lit/decorators.js';
+
+@customElement('{{ tag_name }}')
+export class {{ name }} extends LitElement {
+  static styles = css`
+{{ css }}  `;
+
+  @property({ type: Boolean, reflect: true })
+  disabled = false;
+
+  // Clicks bubble out of the shadow root unless the element is disabled
+  private onClick(event: MouseEvent) {
+{%- else %}
+
+export class {{ name }} extends LitElement {
+  static properties = {
+    disabled: { type: Boolean, reflect: true },
+  };
+
+  static styles = css`
+{{ css }}  `;
+
+  constructor() {
+    super();
+    this.disabled = false;
+  }
+
+  // Clicks bubble out of the shadow root unless the element is disabled
+  onClick(event) {
+{%- endif %}
+    if (this.disabled) {
+      event.stopPropagation();
+    }
+  }
+
+  render() {
+    return html`
+{{ markup }}    `;
+  }
+}
+{%- if typescript %}
+
+declare global {
+  interface HTMLElementTagNameMap {
+    '{{ tag_name }}': {{ name }};
+  }
+}
+{%- else %}
+
+customElements.define('{{ tag_name }}', {{ name }});
+{%- endif %}
+"##).expect("Failed to add lit_component template");
+
+    tera.add_raw_template("lit_package_json", r##"{
+  "name": "{{ package_name }}",
+  "version": "{{ version }}",
+  "description": "{{ name }} custom element <{{ tag_name }}>",
+  "type": "module",
+  "main": "dist/index.js",
+  "module": "dist/index.js",
+  "types": "dist/index.d.ts",
+  "style": "styles/tokens.css",
+  "files": [
+    "dist",
+    "styles"
+  ],
+  "sideEffects": [
+    "dist/{{ name }}.js"
+  ],
+  "scripts": {
+    "build": "tsc -p tsconfig.json",
+    "prepublishOnly": "npm run build"
+  },
+  "dependencies": {
+    "lit": "^3.0.0"
+  },
+  "devDependencies": {
+    "typescript": "^5.0.0"
+  },
+  "license": "MIT"
+}
+"##).expect("Failed to add lit_package_json template");
+}
+
+// Legacy decorators with define semantics off, as Lit's decorators expect
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2021",
+    "module": "ES2020",
+    "moduleResolution": "bundler",
+    "lib": ["ES2021", "DOM", "DOM.Iterable"],
+    "outDir": "./dist",
+    "rootDir": "./src",
+    "declaration": true,
+    "allowJs": true,
+    "strict": true,
+    "experimentalDecorators": true,
+    "useDefineForClassFields": false,
+    "skipLibCheck": true
+  },
+  "include": ["src"],
+  "exclude": ["node_modules", "dist"]
+}
+"#;
+
+fn dialect() -> Dialect {
+    Dialect {
+        root_attributes: vec![
+            "aria-disabled=${this.disabled ? 'true' : nothing}".to_string(),
+            "@click=${this.onClick}".to_string(),
+        ],
+        children: "<slot></slot>".to_string(),
+        interpolate: |literal| format!("${{{}}}", literal),
+    }
+}
+
+pub fn generate(
+    tera: &Tera,
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    let tag_name = tag_name(request.tag_prefix.as_deref(), &request.name)?;
+    let classes = class_names(root);
+
+    let mut context = Context::new();
+    context.insert("name", &request.name);
+    context.insert("tag_name", &tag_name);
+    context.insert("typescript", &request.typescript);
+    context.insert("package_name", &request.package_name);
+    context.insert("version", &request.version);
+    context.insert("markup", &render_template(root, &classes, &dialect(), 3));
+    context.insert("css", &indent(&render_stylesheet(root, &classes), 2));
+
+    let component_code = tera.render("lit_component", &context)
+        .map_err(|e| format!("Component template error: {}", e))?;
+
+    let package_json = tera.render("lit_package_json", &context)
+        .map_err(|e| format!("Package.json template error: {}", e))?;
+
+    // tsc keeps import paths as written, so they carry the emitted extension
+    let framework = Framework::Lit;
+    let index_ts = format!(
+        "export {{ {} }} from './{}.js';\nexport * from './tokens.js';\nexport * from './themes.js';\n",
+        request.name, request.name
+    );
+
+    Ok(GeneratedPackage {
+        framework,
+        styling: Styling::Inline,
+        component_filename: framework.component_filename(&request.name, request.typescript),
+        component_code,
+        package_json,
+        index_ts,
+        tokens: render_files(tokens),
+        extra_files: vec![GeneratedFile::new("tsconfig.json", TSCONFIG)],
+    })
+}
+
+fn indent(text: &str, levels: usize) -> String {
+    let pad = "  ".repeat(levels);
+    text.lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", pad, line) })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::builder::generate_package_files_from_node;
+    use crate::generator::test_support::{button, generate, request};
+    use crate::tokens::model::TokenSet;
+
+    #[test]
+    fn layers_become_a_custom_element_with_shadow_styles() {
+        let package = generate(&request("lit"), &button());
+        let code = &package.component_code;
+
+        assert!(code.contains("@customElement('ui-button')\nexport class Button extends LitElement {\n"));
+        assert!(code.contains(
+            "  static styles = css`\n    .button {\n      background: #0055ff;\n      border-radius: 4px;\n      \
+             padding: 8px 16px;\n    }\n\n    .label {\n"
+        ));
+        assert!(code.contains(
+            "    return html`\n      <div\n        class=\"button\"\n        data-figma-id=\"1:1\"\n        \
+             aria-disabled=${this.disabled ? 'true' : nothing}\n        @click=${this.onClick}\n      >\n"
+        ));
+        assert!(code.contains("          ${'Click me'}\n        </span>\n        <slot></slot>\n      </div>\n    `;\n"));
+        assert!(code.ends_with("    'ui-button': Button;\n  }\n}\n"));
+        assert_eq!(package.component_filename, "Button.ts");
+        assert!(package.index_ts.starts_with("export { Button } from './Button.js';\n"));
+    }
+
+    #[test]
+    fn javascript_elements_are_defined_without_decorators() {
+        let mut request = request("lit");
+        request.typescript = false;
+        request.tag_prefix = Some("acme".to_string());
+        let package = generate(&request, &button());
+
+        assert!(!package.component_code.contains("@customElement"));
+        assert!(package.component_code.contains("  static properties = {\n    disabled: { type: Boolean, reflect: true },\n  };\n"));
+        assert!(package.component_code.ends_with("customElements.define('acme-button', Button);\n"));
+        assert_eq!(package.component_filename, "Button.js");
+    }
+
+    #[test]
+    fn the_package_registers_the_element_as_a_side_effect() {
+        let package = generate(&request("lit"), &button());
+        let package_json: serde_json::Value = serde_json::from_str(&package.package_json).unwrap();
+        assert_eq!(package_json["description"], "Button custom element <ui-button>");
+        assert_eq!(package_json["sideEffects"][0], "dist/Button.js");
+        assert_eq!(package_json["dependencies"]["lit"], "^3.0.0");
+    }
+
+    #[test]
+    fn invalid_tag_prefixes_are_rejected() {
+        let mut request = request("lit");
+        request.tag_prefix = Some("Acme".to_string());
+        let Err(error) = generate_package_files_from_node(&request, &button(), &TokenSet::default()) else {
+            panic!("Acme is not a valid prefix");
+        };
+        assert!(error.starts_with("Invalid tag_prefix: Acme."));
+    }
+}
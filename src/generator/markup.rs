@@ -0,0 +1,253 @@
+// HTML-like templates and stylesheets for the frameworks that separate
+// markup from styles (everything except React). Each element gets a class
+// named after its Figma layer, and its styles become a rule for that class.
+use std::collections::{BTreeMap, HashSet};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::{js_string, ElementNode};
+use crate::tokens::css::TokenLookup;
+use crate::tokens::model::token_segment;
+
+// Framework-specific parts of a template
+pub struct Dialect {
+    // Extra attributes on the root element, e.g. the click binding
+    pub root_attributes: Vec<String>,
+    // Where children passed to the component are rendered, e.g. "<slot />"
+    pub children: String,
+    // Wrap a JS string literal as text content, e.g. "{{ 'Label' }}"
+    pub interpolate: fn(&str) -> String,
+}
+
+// Used when a request has no tag_prefix
+pub const DEFAULT_TAG_PREFIX: &str = "ui";
+
+const VOID_TAGS: &[&str] = &["area", "br", "hr", "img", "input", "source", "wbr"];
+
+// The single element of a component built from a request's config map,
+// with literal values swapped for the tokens they match
+pub fn element_from_config(request: &CreateComponentRequest, lookup: &TokenLookup) -> ElementNode {
+    let mut styles: BTreeMap<String, String> = request
+        .config
+        .iter()
+        .map(|(key, value)| (key.clone(), lookup.style_value(key, value).unwrap_or_else(|| value.clone())))
+        .collect();
+    styles.insert("cursor".to_string(), "pointer".to_string());
+
+    ElementNode {
+        id: String::new(),
+        name: request.name.clone(),
+        tag: request.component_type.clone(),
+        styles,
+        text: None,
+        children: Vec::new(),
+    }
+}
+
+// Custom element names and Angular selectors need a hyphen, so the prefix
+// is required:
+// "ui" and "CustomButton" give "ui-custom-button"
+pub fn tag_name(prefix: Option<&str>, name: &str) -> Result<String, String> {
+    let prefix = prefix.unwrap_or(DEFAULT_TAG_PREFIX);
+    let valid = prefix.starts_with(|c: char| c.is_ascii_lowercase())
+        && !prefix.ends_with('-')
+        && prefix.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!(
+            "Invalid tag_prefix: {}. Use lowercase letters, digits and hyphens, starting with a letter",
+            prefix
+        ));
+    }
+    Ok(format!("{}-{}", prefix, token_segment(name)))
+}
+
+// Class name per element in document order, unique within the component:
+// "Primary Button" is "primary-button", a second "Label" is "label-2".
+// Selectors can't start with a digit, so a "24px" layer is "l-24px".
+pub fn class_names(root: &ElementNode) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut used: HashSet<String> = HashSet::new();
+    collect_class_names(root, &mut used, &mut names);
+    names
+}
+
+fn collect_class_names(element: &ElementNode, used: &mut HashSet<String>, names: &mut Vec<String>) {
+    let mut base = token_segment(&element.name);
+    if base.starts_with(|c: char| c.is_ascii_digit()) {
+        base = format!("l-{}", base);
+    }
+    // "Label", "Label", "Label 2" must not give "label-2" twice
+    let mut name = base.clone();
+    let mut count = 1;
+    while used.contains(&name) {
+        count += 1;
+        name = format!("{}-{}", base, count);
+    }
+    used.insert(name.clone());
+    names.push(name);
+    for child in &element.children {
+        collect_class_names(child, used, names);
+    }
+}
+
+pub fn render_template(root: &ElementNode, classes: &[String], dialect: &Dialect, indent: usize) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+    write_element(root, classes, &mut index, dialect, indent, true, &mut out);
+    out
+}
+
+fn write_element(
+    element: &ElementNode,
+    classes: &[String],
+    index: &mut usize,
+    dialect: &Dialect,
+    indent: usize,
+    is_root: bool,
+    out: &mut String,
+) {
+    let pad = "  ".repeat(indent);
+    let class = &classes[*index];
+    *index += 1;
+
+    out.push_str(&format!("{}<{}\n", pad, element.tag));
+    out.push_str(&format!("{}  class=\"{}\"\n", pad, class));
+    if !element.id.is_empty() {
+        out.push_str(&format!("{}  data-figma-id=\"{}\"\n", pad, element.id));
+    }
+    if is_root {
+        for attribute in &dialect.root_attributes {
+            out.push_str(&format!("{}  {}\n", pad, attribute));
+        }
+    }
+
+    if VOID_TAGS.contains(&element.tag.as_str()) {
+        out.push_str(&format!("{}/>\n", pad));
+        return;
+    }
+    out.push_str(&format!("{}>\n", pad));
+
+    if let Some(text) = &element.text {
+        out.push_str(&format!("{}  {}\n", pad, (dialect.interpolate)(&template_string(text))));
+    }
+    for child in &element.children {
+        write_element(child, classes, index, dialect, indent + 1, false, out);
+    }
+    if is_root {
+        out.push_str(&format!("{}  {}\n", pad, dialect.children));
+    }
+
+    out.push_str(&format!("{}</{}>\n", pad, element.tag));
+}
+
+// Braces are escaped so a "}}" in the text can't end the interpolation
+fn template_string(text: &str) -> String {
+    js_string(text).replace('{', "\\u007b").replace('}', "\\u007d")
+}
+
+// One rule per element that has styles
+pub fn render_stylesheet(root: &ElementNode, classes: &[String]) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+    write_rules(root, classes, &mut index, &mut out);
+    out
+}
+
+fn write_rules(element: &ElementNode, classes: &[String], index: &mut usize, out: &mut String) {
+    let class = &classes[*index];
+    *index += 1;
+
+    if !element.styles.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!(".{} {{\n", class));
+        for (property, value) in &element.styles {
+            out.push_str(&format!("  {}: {};\n", css_property(property), value));
+        }
+        out.push_str("}\n");
+    }
+    for child in &element.children {
+        write_rules(child, classes, index, out);
+    }
+}
+
+// Style keys are React-style camelCase: "borderRadius" is "border-radius"
+pub fn css_property(key: &str) -> String {
+    let mut out = String::new();
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            out.push('-');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// CSS box shorthand: one to four values for top, right, bottom, left
+pub fn box_sides(value: &str) -> Vec<(&'static str, &str)> {
+    let values: Vec<&str> = value.split_whitespace().collect();
+    let (top, right, bottom, left) = match values.as_slice() {
+        [all] => (*all, *all, *all, *all),
+        [vertical, horizontal] => (*vertical, *horizontal, *vertical, *horizontal),
+        [top, horizontal, bottom] => (*top, *horizontal, *bottom, *horizontal),
+        [top, right, bottom, left] => (*top, *right, *bottom, *left),
+        _ => return Vec::new(),
+    };
+    vec![("Top", top), ("Right", right), ("Bottom", bottom), ("Left", left)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::test_support::element;
+
+    #[test]
+    fn class_names_are_unique_and_valid_selectors() {
+        let root = element(
+            "1:1",
+            "Primary Button",
+            &[],
+            None,
+            vec![
+                element("1:2", "Label", &[], None, vec![]),
+                element("1:3", "Label", &[], None, vec![]),
+                element("1:4", "Label 2", &[], None, vec![]),
+                element("1:5", "24px", &[], None, vec![]),
+            ],
+        );
+        assert_eq!(class_names(&root), ["primary-button", "label", "label-2", "label-2-2", "l-24px"]);
+    }
+
+    #[test]
+    fn tag_names_need_a_valid_prefix() {
+        assert_eq!(tag_name(None, "CustomButton").unwrap(), "ui-custom-button");
+        assert_eq!(tag_name(Some("acme-x2"), "Card").unwrap(), "acme-x2-card");
+        for prefix in ["", "Acme", "2x", "acme-", "ac_me"] {
+            assert!(tag_name(Some(prefix), "Card").is_err(), "{}", prefix);
+        }
+    }
+
+    #[test]
+    fn void_elements_are_self_closing() {
+        let mut input = element("1:1", "Card", &[], None, vec![]);
+        input.tag = "input".to_string();
+        let dialect = Dialect {
+            root_attributes: vec!["@click=\"onClick\"".to_string()],
+            children: "<slot />".to_string(),
+            interpolate: |literal| format!("{{{{ {} }}}}", literal),
+        };
+        assert_eq!(
+            render_template(&input, &["card".to_string()], &dialect, 0),
+            "<input\n  class=\"card\"\n  data-figma-id=\"1:1\"\n  @click=\"onClick\"\n/>\n"
+        );
+    }
+
+    #[test]
+    fn box_shorthands_expand_to_four_sides() {
+        assert_eq!(box_sides("4px"), [("Top", "4px"), ("Right", "4px"), ("Bottom", "4px"), ("Left", "4px")]);
+        assert_eq!(box_sides("1px 2px 3px"), [("Top", "1px"), ("Right", "2px"), ("Bottom", "3px"), ("Left", "2px")]);
+        assert!(box_sides("1px 2px 3px 4px 5px").is_empty());
+        assert_eq!(css_property("borderTopLeftRadius"), "border-top-left-radius");
+    }
+}
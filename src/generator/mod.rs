@@ -2,8 +2,24 @@ pub mod routes;
 pub mod models;
 pub mod builder;
 pub mod filesystem;
+// Local npm builds are not wired into the routes yet
+#[allow(dead_code)]
 pub mod npm;
 pub mod config;
 pub mod docker;
+pub mod jobs;
+pub mod node_tree;
+pub mod layout;
+pub mod sources;
+pub mod framework;
+pub mod markup;
+pub mod vue;
+pub mod svelte;
+pub mod lit;
+pub mod angular;
+pub mod react_native;
+pub mod tailwind;
+#[cfg(test)]
+mod test_support;
 
 pub use routes::config as configure_routes;
\ No newline at end of file
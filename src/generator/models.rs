@@ -1,20 +1,96 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use crate::generator::jobs::BuildJob;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateComponentRequest {
     pub component_type: String,  // "button", "card", etc.
     pub name: String,           // "CustomButton"
     pub config: HashMap<String, String>,  // style properties
-    pub framework: String,      // "react"
+    pub framework: String,      // "react", "react-native", "vue", "svelte", "lit" or "angular"
     pub typescript: bool,
     pub package_name: String,   // "@mycompany/custom-button"
     pub version: String,        // "1.0.0"
+    #[serde(default)]
+    pub tokens_file_key: Option<String>,  // use design tokens from a stored Figma file
+    #[serde(default)]
+    pub theme: Option<String>,  // "dark": variable mode used as the default theme
+    #[serde(default)]
+    pub tag_prefix: Option<String>,  // element prefix for "lit" and "angular", defaults to "ui"
+    #[serde(default)]
+    pub styling: Option<String>,  // "inline" (default), "tailwind", "styled-components" or "css-modules"
+    #[serde(default)]
+    pub tailwind_theme: Option<TailwindScales>,  // scales of the consumer's Tailwind theme to snap to
 }
 
+// Theme section to key to value, as in tailwind.config.js,
+// e.g. {"spacing": {"sm": "8px", "md": "16px"}}
+pub type TailwindScales = BTreeMap<String, BTreeMap<String, String>>;
+
 #[derive(Debug, Serialize)]
 pub struct CreateComponentResponse {
     pub success: bool,
     pub message: String,
     pub job_id: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub success: bool,
+    pub message: String,
+    pub job: Option<BuildJob>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobsParams {
+    pub package_name: Option<String>,
+    #[serde(default)]
+    pub skip: u64,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobListResponse {
+    pub success: bool,
+    pub message: String,
+    pub jobs: Vec<BuildJob>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreateComponentFromNodeRequest {
+    pub file_key: String,
+    pub node_id: String,
+    pub name: Option<String>,    // defaults to the Figma layer name
+    pub framework: String,       // "react", "react-native", "vue", "svelte", "lit" or "angular"
+    pub typescript: bool,
+    pub package_name: String,
+    pub version: String,
+    #[serde(default)]
+    pub theme: Option<String>,   // defaults to the mode selected in Figma
+    #[serde(default)]
+    pub tag_prefix: Option<String>,
+    #[serde(default)]
+    pub styling: Option<String>,
+    #[serde(default)]
+    pub tailwind_theme: Option<TailwindScales>,
+}
+
+impl CreateComponentFromNodeRequest {
+    // Map onto the regular request so packaging stays shared
+    pub fn to_component_request(&self, name: String) -> CreateComponentRequest {
+        CreateComponentRequest {
+            component_type: "div".to_string(),
+            name,
+            config: HashMap::new(),
+            framework: self.framework.clone(),
+            typescript: self.typescript,
+            package_name: self.package_name.clone(),
+            version: self.version.clone(),
+            tokens_file_key: Some(self.file_key.clone()),
+            theme: self.theme.clone(),
+            tag_prefix: self.tag_prefix.clone(),
+            styling: self.styling.clone(),
+            tailwind_theme: self.tailwind_theme.clone(),
+        }
+    }
+}
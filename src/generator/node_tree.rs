@@ -0,0 +1,587 @@
+use std::collections::BTreeMap;
+use crate::figma::models::{Color, Effect, EffectType, Node, NodeType, Paint, PaintType};
+use crate::generator::layout;
+use crate::tokens::css::TokenLookup;
+
+// A single element of the generated component tree, built from a Figma node
+#[derive(Debug, Clone)]
+pub struct ElementNode {
+    pub id: String,
+    pub name: String,
+    pub tag: String,
+    pub styles: BTreeMap<String, String>,
+    pub text: Option<String>,
+    pub children: Vec<ElementNode>,
+}
+
+impl ElementNode {
+    // The element and all its descendants in document order
+    pub fn flatten(&self) -> Vec<&ElementNode> {
+        let mut elements = vec![self];
+        for child in &self.children {
+            elements.extend(child.flatten());
+        }
+        elements
+    }
+}
+
+// Convert a stored Figma node (the `document` of a node response, or any node
+// inside a file tree) into an element tree. Returns None for hidden nodes.
+pub fn from_figma_node(node: &Node) -> Option<ElementNode> {
+    convert_node(node, None)
+}
+
+fn convert_node(node: &Node, parent: Option<&Node>) -> Option<ElementNode> {
+    if !node.visible {
+        return None;
+    }
+
+    let mut styles = BTreeMap::new();
+
+    // Size and position from the bounding box
+    if let Some(bbox) = &node.absolute_bounding_box {
+        styles.insert("width".to_string(), px(bbox.width));
+        styles.insert("height".to_string(), px(bbox.height));
+    }
+
+    // Auto-layout children flow as flex items; everything else is placed
+    // relative to its parent
+    if let Some(parent) = parent
+        && parent.is_auto_layout()
+        && !layout::is_absolute(node)
+    {
+        layout::apply_child_layout(node, parent, &mut styles);
+    } else if let Some(parent) = parent
+        && let (Some(own), Some(parent_box)) = (&node.absolute_bounding_box, &parent.absolute_bounding_box)
+    {
+        styles.insert("position".to_string(), "absolute".to_string());
+        styles.insert("left".to_string(), px(own.x - parent_box.x));
+        styles.insert("top".to_string(), px(own.y - parent_box.y));
+    }
+
+    if let Some(opacity) = node.opacity
+        && opacity < 1.0
+    {
+        styles.insert("opacity".to_string(), format_number(opacity));
+    }
+
+    let is_text = node.node_type == NodeType::Text;
+    let text = if is_text {
+        apply_text_styles(node, &mut styles);
+        Some(node.characters.clone().unwrap_or_default())
+    } else {
+        apply_box_styles(node, &mut styles);
+        layout::apply_auto_layout(node, &mut styles);
+        None
+    };
+
+    let children: Vec<ElementNode> = node
+        .children
+        .iter()
+        .filter_map(|child| convert_node(child, Some(node)))
+        .collect();
+
+    // Absolutely positioned children need a positioned container
+    if children.iter().any(|c| c.styles.get("position").map(String::as_str) == Some("absolute"))
+        && !styles.contains_key("position")
+    {
+        styles.insert("position".to_string(), "relative".to_string());
+    }
+
+    Some(ElementNode {
+        id: node.id.clone(),
+        name: node.name.clone(),
+        tag: if is_text { "span" } else { "div" }.to_string(),
+        styles,
+        text,
+        children,
+    })
+}
+
+fn apply_box_styles(node: &Node, styles: &mut BTreeMap<String, String>) {
+    if let Some(color) = first_solid_color(&node.fills) {
+        styles.insert("background".to_string(), color);
+    }
+
+    if let Some(color) = first_solid_color(&node.strokes) {
+        let weight = node.stroke_weight.unwrap_or(1.0);
+        styles.insert("border".to_string(), format!("{} solid {}", px(weight), color));
+        styles.insert("boxSizing".to_string(), "border-box".to_string());
+    }
+
+    if node.node_type == NodeType::Ellipse {
+        styles.insert("borderRadius".to_string(), "50%".to_string());
+    } else if let Some(radii) = &node.rectangle_corner_radii {
+        let radii: Vec<String> = radii.iter().copied().map(px).collect();
+        styles.insert("borderRadius".to_string(), radii.join(" "));
+    } else if let Some(radius) = node.corner_radius {
+        styles.insert("borderRadius".to_string(), px(radius));
+    }
+
+    if node.clips_content == Some(true) {
+        styles.insert("overflow".to_string(), "hidden".to_string());
+    }
+
+    let shadows: Vec<String> = node.effects.iter().filter_map(shadow).collect();
+    if !shadows.is_empty() {
+        styles.insert("boxShadow".to_string(), shadows.join(", "));
+    }
+}
+
+fn apply_text_styles(node: &Node, styles: &mut BTreeMap<String, String>) {
+    if let Some(color) = first_solid_color(&node.fills) {
+        styles.insert("color".to_string(), color);
+    }
+
+    let Some(style) = &node.style else {
+        return;
+    };
+
+    if let Some(family) = &style.font_family {
+        styles.insert("fontFamily".to_string(), family.clone());
+    }
+    if let Some(size) = style.font_size {
+        styles.insert("fontSize".to_string(), px(size));
+    }
+    if let Some(weight) = style.font_weight {
+        styles.insert("fontWeight".to_string(), format_number(weight));
+    }
+    if style.italic == Some(true) {
+        styles.insert("fontStyle".to_string(), "italic".to_string());
+    }
+    if let Some(line_height) = style.line_height_px {
+        styles.insert("lineHeight".to_string(), px(line_height));
+    }
+    if let Some(spacing) = style.letter_spacing
+        && spacing != 0.0
+    {
+        styles.insert("letterSpacing".to_string(), px(spacing));
+    }
+    if let Some(align) = &style.text_align_horizontal {
+        let align = match align.as_str() {
+            "CENTER" => "center",
+            "RIGHT" => "right",
+            "JUSTIFIED" => "justify",
+            _ => "left",
+        };
+        styles.insert("textAlign".to_string(), align.to_string());
+    }
+    match style.text_decoration.as_deref() {
+        Some("UNDERLINE") => {
+            styles.insert("textDecoration".to_string(), "underline".to_string());
+        }
+        Some("STRIKETHROUGH") => {
+            styles.insert("textDecoration".to_string(), "line-through".to_string());
+        }
+        _ => {}
+    }
+    match style.text_case.as_deref() {
+        Some("UPPER") => {
+            styles.insert("textTransform".to_string(), "uppercase".to_string());
+        }
+        Some("LOWER") => {
+            styles.insert("textTransform".to_string(), "lowercase".to_string());
+        }
+        Some("TITLE") => {
+            styles.insert("textTransform".to_string(), "capitalize".to_string());
+        }
+        _ => {}
+    }
+}
+
+// Only the first visible solid paint is used; gradients and images are skipped
+fn first_solid_color(paints: &[Paint]) -> Option<String> {
+    paints.iter().find_map(|paint| {
+        if !paint.visible || paint.paint_type != PaintType::Solid {
+            return None;
+        }
+        paint.color.as_ref().map(|color| css_color(color, paint.opacity))
+    })
+}
+
+fn shadow(effect: &Effect) -> Option<String> {
+    if !effect.visible {
+        return None;
+    }
+    let inset = match effect.effect_type {
+        EffectType::DropShadow => "",
+        EffectType::InnerShadow => "inset ",
+        _ => return None,
+    };
+    let (x, y) = effect.offset.map(|o| (o.x, o.y)).unwrap_or((0.0, 0.0));
+    let spread = effect.spread.unwrap_or(0.0);
+    let color = effect.color.as_ref().map(|c| css_color(c, 1.0)).unwrap_or_else(|| "#000000".to_string());
+    Some(format!("{}{} {} {} {} {}", inset, px(x), px(y), px(effect.radius), px(spread), color))
+}
+
+// Figma colors are 0..1 floats; opaque colors become hex, the rest rgba()
+pub fn css_color(color: &Color, opacity: f64) -> String {
+    let (r, g, b) = (to_byte(color.r), to_byte(color.g), to_byte(color.b));
+    let alpha = color.a * opacity;
+
+    if alpha >= 1.0 {
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        format!("rgba({}, {}, {}, {})", r, g, b, format_number(alpha))
+    }
+}
+
+fn to_byte(channel: f64) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+pub fn px(value: f64) -> String {
+    format!("{}px", format_number(value))
+}
+
+// Round to two decimals and drop trailing zeros
+pub fn format_number(value: f64) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+// Turn a Figma layer name like "Primary button / hover" into "PrimaryButtonHover"
+pub fn component_name(layer_name: &str) -> String {
+    let name: String = layer_name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    if name.is_empty() {
+        "Component".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Component{}", name)
+    } else {
+        name
+    }
+}
+
+// Swap literal style values for the design tokens they match
+pub fn apply_tokens(element: &mut ElementNode, lookup: &TokenLookup) {
+    for (property, value) in element.styles.iter_mut() {
+        if let Some(reference) = lookup.style_value(property, value) {
+            *value = reference;
+        }
+    }
+    for child in &mut element.children {
+        apply_tokens(child, lookup);
+    }
+}
+
+// How render_jsx attaches each element's styles
+pub enum JsxStyling<'a> {
+    // style={{ ... }} objects
+    Inline,
+    // className with utility classes, one entry per element in document order
+    Classes(&'a [String]),
+    // Styled components used in place of the tags, one name per element in
+    // document order
+    Components(&'a [String]),
+    // className from the imported `styles` module, one class per element in
+    // document order
+    CssModules(&'a [String]),
+}
+
+// Render the element tree as JSX. The root element receives the component's
+// className / onClick props and renders any passed children last.
+pub fn render_jsx(root: &ElementNode, indent: usize, styling: &JsxStyling) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+    write_element(root, indent, true, styling, &mut index, &mut out);
+    out
+}
+
+fn write_element(
+    element: &ElementNode,
+    indent: usize,
+    is_root: bool,
+    styling: &JsxStyling,
+    index: &mut usize,
+    out: &mut String,
+) {
+    let pad = "  ".repeat(indent);
+    let position = *index;
+    *index += 1;
+
+    let tag = match styling {
+        JsxStyling::Components(names) => names[position].as_str(),
+        _ => element.tag.as_str(),
+    };
+    out.push_str(&format!("{}<{}\n", pad, tag));
+    if !element.id.is_empty() {
+        out.push_str(&format!("{}  data-figma-id=\"{}\"\n", pad, element.id));
+    }
+    match styling {
+        JsxStyling::Inline if !element.styles.is_empty() => {
+            out.push_str(&format!("{}  style={{{{ {} }}}}\n", pad, style_object(&element.styles)));
+        }
+        JsxStyling::Classes(classes) if is_root => {
+            out.push_str(&format!(
+                "{}  className={{[{}, className].filter(Boolean).join(' ')}}\n",
+                pad,
+                js_string(&classes[position])
+            ));
+        }
+        JsxStyling::Classes(classes) if !classes[position].is_empty() => {
+            out.push_str(&format!("{}  className={}\n", pad, jsx_attribute(&classes[position])));
+        }
+        JsxStyling::CssModules(classes) if is_root => {
+            out.push_str(&format!(
+                "{}  className={{[styles[{}], className].filter(Boolean).join(' ')}}\n",
+                pad,
+                js_string(&classes[position])
+            ));
+        }
+        JsxStyling::CssModules(classes) if !element.styles.is_empty() => {
+            out.push_str(&format!("{}  className={{styles[{}]}}\n", pad, js_string(&classes[position])));
+        }
+        _ => {}
+    }
+    if is_root {
+        out.push_str(&format!("{}  onClick={{onClick}}\n", pad));
+        if !matches!(styling, JsxStyling::Classes(_) | JsxStyling::CssModules(_)) {
+            out.push_str(&format!("{}  className={{className}}\n", pad));
+        }
+    }
+    out.push_str(&format!("{}>\n", pad));
+
+    if let Some(text) = &element.text {
+        out.push_str(&format!("{}  {{{}}}\n", pad, js_string(text)));
+    }
+    for child in &element.children {
+        write_element(child, indent + 1, false, styling, index, out);
+    }
+    if is_root {
+        out.push_str(&format!("{}  {{children}}\n", pad));
+    }
+
+    out.push_str(&format!("{}</{}>\n", pad, tag));
+}
+
+fn style_object(styles: &BTreeMap<String, String>) -> String {
+    styles
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, js_string(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// JSX attribute strings have no escapes, so values that would end the
+// string or read as an entity are passed as an expression instead
+fn jsx_attribute(value: &str) -> String {
+    if value.contains(['"', '&']) {
+        format!("{{{}}}", js_string(value))
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+// Single-quoted JS string literal, matching the style of the templates
+pub fn js_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('\n', "\\n")
+        .replace('\r', "");
+    format!("'{}'", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn convert(document: serde_json::Value) -> ElementNode {
+        from_figma_node(&serde_json::from_value(document).unwrap()).unwrap()
+    }
+
+    fn solid(r: f64, g: f64, b: f64) -> serde_json::Value {
+        json!([{ "type": "SOLID", "color": { "r": r, "g": g, "b": b } }])
+    }
+
+    #[test]
+    fn hidden_nodes_are_dropped() {
+        let root = convert(json!({
+            "id": "1:1",
+            "name": "Card",
+            "type": "FRAME",
+            "children": [
+                { "id": "1:2", "type": "RECTANGLE", "visible": false },
+                { "id": "1:3", "type": "RECTANGLE" },
+            ],
+        }));
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].id, "1:3");
+        let hidden: Node = serde_json::from_value(json!({ "id": "1:1", "type": "FRAME", "visible": false })).unwrap();
+        assert!(from_figma_node(&hidden).is_none());
+    }
+
+    #[test]
+    fn free_children_are_placed_relative_to_their_parent() {
+        let root = convert(json!({
+            "id": "1:1",
+            "type": "FRAME",
+            "absoluteBoundingBox": { "x": 100.0, "y": 50.0, "width": 320.0, "height": 200.0 },
+            "children": [{
+                "id": "1:2",
+                "type": "RECTANGLE",
+                "absoluteBoundingBox": { "x": 116.0, "y": 74.5, "width": 40.0, "height": 40.0 },
+            }],
+        }));
+        assert_eq!(root.styles["width"], "320px");
+        assert_eq!(root.styles["height"], "200px");
+        assert_eq!(root.styles["position"], "relative");
+        let child = &root.children[0].styles;
+        assert_eq!(child["position"], "absolute");
+        assert_eq!(child["left"], "16px");
+        assert_eq!(child["top"], "24.5px");
+    }
+
+    #[test]
+    fn auto_layout_children_flow_as_flex_items() {
+        let root = convert(json!({
+            "id": "1:1",
+            "type": "FRAME",
+            "layoutMode": "HORIZONTAL",
+            "absoluteBoundingBox": { "x": 0.0, "y": 0.0, "width": 200.0, "height": 40.0 },
+            "children": [
+                {
+                    "id": "1:2",
+                    "type": "FRAME",
+                    "layoutGrow": 1.0,
+                    "absoluteBoundingBox": { "x": 0.0, "y": 0.0, "width": 100.0, "height": 40.0 },
+                },
+                {
+                    "id": "1:3",
+                    "type": "FRAME",
+                    "layoutPositioning": "ABSOLUTE",
+                    "absoluteBoundingBox": { "x": 180.0, "y": 0.0, "width": 20.0, "height": 20.0 },
+                },
+            ],
+        }));
+        assert_eq!(root.styles["display"], "flex");
+        assert_eq!(root.children[0].styles["flexGrow"], "1");
+        assert!(!root.children[0].styles.contains_key("position"));
+        assert_eq!(root.children[1].styles["position"], "absolute");
+        assert_eq!(root.children[1].styles["left"], "180px");
+        assert_eq!(root.styles["position"], "relative");
+    }
+
+    #[test]
+    fn frames_get_box_styles() {
+        let root = convert(json!({
+            "id": "1:1",
+            "type": "FRAME",
+            "fills": [
+                { "type": "GRADIENT_LINEAR" },
+                { "type": "SOLID", "color": { "r": 0.0, "g": 0.0, "b": 0.0 }, "visible": false },
+                { "type": "SOLID", "color": { "r": 0.0, "g": 0.333, "b": 1.0 } },
+            ],
+            "strokes": solid(1.0, 0.0, 0.0),
+            "strokeWeight": 2.0,
+            "rectangleCornerRadii": [4.0, 4.0, 0.0, 0.0],
+            "clipsContent": true,
+            "opacity": 0.5,
+            "effects": [
+                {
+                    "type": "DROP_SHADOW",
+                    "radius": 4.0,
+                    "offset": { "x": 0.0, "y": 2.0 },
+                    "color": { "r": 0.0, "g": 0.0, "b": 0.0, "a": 0.25 },
+                },
+                { "type": "INNER_SHADOW", "radius": 1.0, "spread": 1.0 },
+                { "type": "LAYER_BLUR", "radius": 8.0 },
+            ],
+        }));
+        assert_eq!(root.tag, "div");
+        assert_eq!(root.styles["background"], "#0055ff");
+        assert_eq!(root.styles["border"], "2px solid #ff0000");
+        assert_eq!(root.styles["borderRadius"], "4px 4px 0px 0px");
+        assert_eq!(root.styles["overflow"], "hidden");
+        assert_eq!(root.styles["opacity"], "0.5");
+        assert_eq!(
+            root.styles["boxShadow"],
+            "0px 2px 4px 0px rgba(0, 0, 0, 0.25), inset 0px 0px 1px 1px #000000"
+        );
+
+        let ellipse = convert(json!({ "id": "1:2", "type": "ELLIPSE", "cornerRadius": 3.0 }));
+        assert_eq!(ellipse.styles["borderRadius"], "50%");
+    }
+
+    #[test]
+    fn text_nodes_become_spans_with_text_styles() {
+        let text = convert(json!({
+            "id": "1:2",
+            "name": "Label",
+            "type": "TEXT",
+            "characters": "Click me",
+            "fills": solid(1.0, 1.0, 1.0),
+            "style": {
+                "fontFamily": "Inter",
+                "fontSize": 14.0,
+                "fontWeight": 600.0,
+                "italic": true,
+                "lineHeightPx": 16.94,
+                "letterSpacing": 0.0,
+                "textAlignHorizontal": "JUSTIFIED",
+                "textDecoration": "UNDERLINE",
+                "textCase": "TITLE",
+            },
+        }));
+        assert_eq!(text.tag, "span");
+        assert_eq!(text.text.as_deref(), Some("Click me"));
+        assert_eq!(text.styles["color"], "#ffffff");
+        assert_eq!(text.styles["fontFamily"], "Inter");
+        assert_eq!(text.styles["fontSize"], "14px");
+        assert_eq!(text.styles["fontWeight"], "600");
+        assert_eq!(text.styles["fontStyle"], "italic");
+        assert_eq!(text.styles["lineHeight"], "16.94px");
+        assert_eq!(text.styles["textAlign"], "justify");
+        assert_eq!(text.styles["textDecoration"], "underline");
+        assert_eq!(text.styles["textTransform"], "capitalize");
+        assert!(!text.styles.contains_key("letterSpacing"));
+        assert!(!text.styles.contains_key("background"));
+    }
+
+    #[test]
+    fn translucent_colors_become_rgba() {
+        let color = Color { r: 1.0, g: 0.5, b: 0.0, a: 0.8 };
+        assert_eq!(css_color(&color, 1.0), "rgba(255, 128, 0, 0.8)");
+        assert_eq!(css_color(&color, 0.5), "rgba(255, 128, 0, 0.4)");
+        assert_eq!(css_color(&Color { a: 1.0, ..color }, 1.0), "#ff8000");
+    }
+
+    #[test]
+    fn layer_names_become_component_names() {
+        assert_eq!(component_name("Primary button / hover"), "PrimaryButtonHover");
+        assert_eq!(component_name("2 column layout"), "Component2ColumnLayout");
+        assert_eq!(component_name("  / "), "Component");
+    }
+
+    #[test]
+    fn jsx_renders_the_tree_with_the_root_props() {
+        let root = convert(json!({
+            "id": "1:1",
+            "type": "FRAME",
+            "fills": solid(0.0, 0.0, 0.0),
+            "children": [{ "id": "1:2", "type": "TEXT", "characters": "It's \"done\"" }],
+        }));
+        let jsx = render_jsx(&root, 0, &JsxStyling::Inline);
+        assert_eq!(
+            jsx,
+            "<div\n  data-figma-id=\"1:1\"\n  style={{ background: '#000000' }}\n  onClick={onClick}\n  className={className}\n>\n  \
+             <span\n    data-figma-id=\"1:2\"\n  >\n    {'It\\'s \"done\"'}\n  </span>\n  {children}\n</div>\n"
+        );
+    }
+}
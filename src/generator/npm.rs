@@ -40,8 +40,7 @@ impl NpmBuilder {
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(io::Error::other(
                 format!("npm install failed: {}", String::from_utf8_lossy(&output.stderr))
             ))
         }
@@ -57,8 +56,7 @@ impl NpmBuilder {
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(io::Error::other(
                 format!("npm build failed: {}", String::from_utf8_lossy(&output.stderr))
             ))
         }
@@ -74,8 +72,7 @@ impl NpmBuilder {
             let filename = String::from_utf8_lossy(&output.stdout).trim().to_string();
             Ok(filename)
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(io::Error::other(
                 format!("npm pack failed: {}", String::from_utf8_lossy(&output.stderr))
             ))
         }
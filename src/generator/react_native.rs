@@ -0,0 +1,450 @@
+// React Native output: View / Text / Pressable elements styled through
+// StyleSheet.create. React Native has no CSS shorthands, units or custom
+// properties, so the web style model is translated property by property.
+use std::collections::BTreeMap;
+use tera::{Context, Tera};
+use crate::generator::builder::{GeneratedPackage, GeneratedFile};
+use crate::generator::framework::{Framework, Styling};
+use crate::generator::markup::{box_sides, class_names};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::{format_number, js_string, ElementNode};
+use crate::tokens::css::render_files;
+use crate::tokens::model::TokenSet;
+
+pub fn add_templates(tera: &mut Tera) {
+    tera.add_raw_template("react_native_component", r##"
+import React from 'react';
+import { Pressable, StyleSheet, Text, View } from 'react-native';
+{%- if typescript %}
+import type { StyleProp, ViewStyle } from 'react-native';
+
+interface {{ name }}Props {
+  children?: React.ReactNode;
+  onPress?: () => void;
+  disabled?: boolean;
+  style?: StyleProp<ViewStyle>;
+}
+{%- endif %}
+
+const {{ name }}{% if typescript %}: React.FC<{{ name }}Props>{% endif %} = ({ children, onPress, disabled, style }) => {
+  return (
+{{ jsx }}  );
+};
+
+const styles = StyleSheet.create({
+{{ styles }}});
+
+export default {{ name }};
+"##).expect("Failed to add react_native_component template");
+
+    tera.add_raw_template("react_native_package_json", r##"{
+  "name": "{{ package_name }}",
+  "version": "{{ version }}",
+  "description": "{{ name }} React Native component",
+  "main": "dist/index.js",
+  "types": "dist/index.d.ts",
+  "react-native": "src/index.ts",
+  "files": [
+    "dist",
+    "src"
+  ],
+  "scripts": {
+    "build": "tsc -p tsconfig.json",
+    "prepublishOnly": "npm run build"
+  },
+  "peerDependencies": {
+    "react": ">=18.0.0",
+    "react-native": ">=0.71.0"
+  },
+  "devDependencies": {
+    "@types/react": "^18.0.0",
+    "react": "^18.2.0",
+    "react-native": "^0.73.0",
+    "typescript": "^5.0.0"
+  },
+  "license": "MIT"
+}
+"##).expect("Failed to add react_native_package_json template");
+}
+
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2019",
+    "module": "ESNext",
+    "moduleResolution": "bundler",
+    "lib": ["ES2019"],
+    "jsx": "react",
+    "outDir": "./dist",
+    "rootDir": "./src",
+    "declaration": true,
+    "allowJs": true,
+    "strict": true,
+    "esModuleInterop": true,
+    "skipLibCheck": true
+  },
+  "include": ["src"],
+  "exclude": ["node_modules", "dist"]
+}
+"#;
+
+// Web-only properties without a React Native equivalent
+const DROPPED: &[&str] = &["boxSizing", "cursor", "transition", "outline"];
+
+// Text properties, which React Native only applies on Text
+const TEXT_ONLY: &[&str] = &[
+    "color", "fontFamily", "fontSize", "fontStyle", "fontWeight", "letterSpacing", "lineHeight",
+    "textAlign", "textDecorationLine", "textTransform",
+];
+
+// Properties that take plain numbers
+const NUMERIC: &[&str] = &["opacity", "flexGrow", "flexShrink", "flexBasis", "zIndex", "aspectRatio"];
+
+pub fn generate(
+    tera: &Tera,
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    // StyleSheet keys are the layer class names, quoted since they hold hyphens
+    let keys = class_names(root);
+    // The root's text styles go on the Text it wraps its text in
+    let (_, root_text_styles) = split_text_styles(native_styles(&root.styles));
+    let text_key = (!root_text_styles.is_empty()).then(|| unused_key(&keys, &format!("{}-text", keys[0])));
+
+    let mut jsx = String::new();
+    let mut index = 0;
+    write_element(root, &keys, &mut index, 2, text_key.as_deref(), &mut jsx);
+
+    let mut styles = String::new();
+    let mut index = 0;
+    write_styles(root, &keys, &mut index, true, &mut styles);
+    if let Some(text_key) = &text_key {
+        write_style(text_key, &root_text_styles, &mut styles);
+    }
+
+    let mut context = Context::new();
+    context.insert("name", &request.name);
+    context.insert("typescript", &request.typescript);
+    context.insert("package_name", &request.package_name);
+    context.insert("version", &request.version);
+    context.insert("jsx", &jsx);
+    context.insert("styles", &styles);
+
+    let component_code = tera.render("react_native_component", &context)
+        .map_err(|e| format!("Component template error: {}", e))?;
+
+    let package_json = tera.render("react_native_package_json", &context)
+        .map_err(|e| format!("Package.json template error: {}", e))?;
+
+    let framework = Framework::ReactNative;
+    let index_ts = format!(
+        "export {{ default as {} }} from './{}';\nexport * from './tokens';\nexport * from './themes';\n",
+        request.name, request.name
+    );
+
+    Ok(GeneratedPackage {
+        framework,
+        styling: Styling::Inline,
+        component_filename: framework.component_filename(&request.name, request.typescript),
+        component_code,
+        package_json,
+        index_ts,
+        tokens: render_files(tokens),
+        extra_files: vec![GeneratedFile::new("tsconfig.json", TSCONFIG)],
+    })
+}
+
+// The root is a Pressable, text layers are Text and everything else a View.
+// Text of the root and passed string children need a Text of their own.
+fn write_element(element: &ElementNode, keys: &[String], index: &mut usize, indent: usize, root_text_key: Option<&str>, out: &mut String) {
+    let pad = "  ".repeat(indent);
+    let key = &keys[*index];
+    let is_root = *index == 0;
+    *index += 1;
+
+    let tag = if is_root {
+        "Pressable"
+    } else if element.text.is_some() {
+        "Text"
+    } else {
+        "View"
+    };
+
+    out.push_str(&format!("{}<{}\n", pad, tag));
+    if !element.id.is_empty() {
+        out.push_str(&format!("{}  testID=\"{}\"\n", pad, element.id));
+    }
+    if is_root {
+        out.push_str(&format!("{}  style={{[styles['{}'], style]}}\n", pad, key));
+        out.push_str(&format!("{}  onPress={{onPress}}\n", pad));
+        out.push_str(&format!("{}  disabled={{disabled}}\n", pad));
+        out.push_str(&format!("{}  accessibilityRole=\"button\"\n", pad));
+    } else {
+        out.push_str(&format!("{}  style={{styles['{}']}}\n", pad, key));
+    }
+    out.push_str(&format!("{}>\n", pad));
+
+    let text_open = match root_text_key {
+        Some(text_key) => format!("<Text style={{styles['{}']}}>", text_key),
+        None => "<Text>".to_string(),
+    };
+    if let Some(text) = &element.text {
+        if is_root {
+            out.push_str(&format!("{}  {}{{{}}}</Text>\n", pad, text_open, js_string(text)));
+        } else {
+            out.push_str(&format!("{}  {{{}}}\n", pad, js_string(text)));
+        }
+    }
+    for child in &element.children {
+        write_element(child, keys, index, indent + 1, None, out);
+    }
+    if is_root {
+        out.push_str(&format!(
+            "{}  {{typeof children === 'string' ? {}{{children}}</Text> : children}}\n",
+            pad, text_open
+        ));
+    }
+
+    out.push_str(&format!("{}</{}>\n", pad, tag));
+}
+
+// Text layers keep all their styles. Views drop text styles, which React
+// Native would ignore there; the root's are written to its own Text key.
+fn write_styles(element: &ElementNode, keys: &[String], index: &mut usize, is_root: bool, out: &mut String) {
+    let key = &keys[*index];
+    *index += 1;
+
+    let styles = native_styles(&element.styles);
+    if !is_root && element.text.is_some() {
+        write_style(key, &styles, out);
+    } else {
+        write_style(key, &split_text_styles(styles).0, out);
+    }
+    for child in &element.children {
+        write_styles(child, keys, index, false, out);
+    }
+}
+
+fn write_style(key: &str, styles: &[(String, String)], out: &mut String) {
+    out.push_str(&format!("  '{}': {{\n", key));
+    for (property, value) in styles {
+        out.push_str(&format!("    {}: {},\n", property, value));
+    }
+    out.push_str("  },\n");
+}
+
+// (view styles, text styles)
+fn split_text_styles(styles: NativeStyles) -> (NativeStyles, NativeStyles) {
+    styles
+        .into_iter()
+        .partition(|(property, _)| !TEXT_ONLY.contains(&property.as_str()))
+}
+
+// `key`, numbered when a layer already uses it
+fn unused_key(keys: &[String], key: &str) -> String {
+    let mut candidate = key.to_string();
+    let mut n = 2;
+    while keys.contains(&candidate) {
+        candidate = format!("{}-{}", key, n);
+        n += 1;
+    }
+    candidate
+}
+
+// Style properties with their values rendered as JS
+pub type NativeStyles = Vec<(String, String)>;
+
+// Translate web styles into React Native style properties
+pub fn native_styles(styles: &BTreeMap<String, String>) -> NativeStyles {
+    let mut out: NativeStyles = Vec::new();
+    let mut push = |property: &str, value: String| out.push((property.to_string(), value));
+
+    for (property, value) in styles {
+        let value = strip_custom_properties(value);
+        let value = value.trim();
+        match property.as_str() {
+            p if DROPPED.contains(&p) => {}
+            // Flex is the only layout React Native has
+            "display" if value != "none" => {}
+            "background" => push("backgroundColor", js_string(value)),
+            "border" => {
+                let mut parts = value.splitn(3, ' ');
+                if let (Some(width), Some(style), Some(color)) = (parts.next(), parts.next(), parts.next()) {
+                    push("borderWidth", dimension(width));
+                    push("borderStyle", js_string(style));
+                    push("borderColor", js_string(color));
+                }
+            }
+            "borderRadius" => {
+                let radii: Vec<&str> = value.split_whitespace().collect();
+                match radii.as_slice() {
+                    // Circles: half the shorter side, when the size is known
+                    ["50%"] => {
+                        let side = ["width", "height"]
+                            .iter()
+                            .filter_map(|key| styles.get(*key).and_then(|v| parse_px(v)))
+                            .fold(None, |min: Option<f64>, v| Some(min.map_or(v, |m| m.min(v))));
+                        if let Some(side) = side {
+                            push("borderRadius", format_number(side / 2.0));
+                        }
+                    }
+                    [all] => push("borderRadius", dimension(all)),
+                    [top_left, top_right, bottom_right, bottom_left] => {
+                        push("borderTopLeftRadius", dimension(top_left));
+                        push("borderTopRightRadius", dimension(top_right));
+                        push("borderBottomRightRadius", dimension(bottom_right));
+                        push("borderBottomLeftRadius", dimension(bottom_left));
+                    }
+                    _ => {}
+                }
+            }
+            "padding" | "margin" => {
+                for (side, side_value) in box_sides(value) {
+                    push(&format!("{}{}", property, side), dimension(side_value));
+                }
+            }
+            "boxShadow" => {
+                // Only the first outer shadow; iOS reads shadow*, Android elevation
+                let first = first_shadow(value);
+                let parts: Vec<&str> = first.splitn(5, ' ').collect();
+                if let [x, y, blur, _spread, color] = parts.as_slice()
+                    && let (Some(x), Some(y), Some(blur)) = (parse_px(x), parse_px(y), parse_px(blur))
+                {
+                    push("shadowColor", js_string(color));
+                    push(
+                        "shadowOffset",
+                        format!("{{ width: {}, height: {} }}", format_number(x), format_number(y)),
+                    );
+                    push("shadowOpacity", "1".to_string());
+                    push("shadowRadius", format_number(blur / 2.0));
+                    push("elevation", format_number((blur / 2.0).round()));
+                }
+            }
+            "textDecoration" => push("textDecorationLine", js_string(value)),
+            // Font weights are strings in React Native
+            "fontWeight" => push("fontWeight", js_string(value)),
+            p if NUMERIC.contains(&p) => match value.parse::<f64>() {
+                Ok(number) => push(p, format_number(number)),
+                Err(_) => push(p, dimension(value)),
+            },
+            p => push(p, dimension(value)),
+        }
+    }
+    out
+}
+
+// Pixels become numbers; percentages and keywords stay strings
+fn dimension(value: &str) -> String {
+    let number = if value == "0" { Some(0.0) } else { parse_px(value) };
+    match number {
+        Some(number) => format_number(number),
+        None => js_string(value),
+    }
+}
+
+fn parse_px(value: &str) -> Option<f64> {
+    value.trim().strip_suffix("px")?.parse().ok()
+}
+
+// Shadows are separated by commas outside of rgba(...)
+fn first_shadow(value: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => return &value[..i],
+            _ => {}
+        }
+    }
+    value
+}
+
+// Token references are written as var(--name, literal); React Native has no
+// custom properties, so they fall back to the literal
+fn strip_custom_properties(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("var(") {
+        out.push_str(&rest[..start]);
+        let inner = &rest[start + 4..];
+        let mut depth = 1;
+        let mut comma = None;
+        let mut end = inner.len();
+        for (i, c) in inner.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = i;
+                        break;
+                    }
+                }
+                ',' if depth == 1 && comma.is_none() => comma = Some(i),
+                _ => {}
+            }
+        }
+        if let Some(comma) = comma {
+            out.push_str(&strip_custom_properties(inner[comma + 1..end].trim()));
+        }
+        rest = inner.get(end + 1..).unwrap_or_default();
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::test_support::{button, element, generate, request};
+
+    #[test]
+    fn layers_become_native_primitives_with_stylesheet_keys() {
+        let package = generate(&request("react-native"), &button());
+        let code = &package.component_code;
+
+        assert!(code.contains("<Pressable\n      testID=\"1:1\"\n      style={[styles['button'], style]}"));
+        assert!(code.contains("<Text\n        testID=\"1:2\"\n        style={styles['label']}\n      >\n        {'Click me'}\n"));
+        assert!(code.contains("  'button': {\n    backgroundColor: '#0055ff',\n    borderRadius: 4,\n    paddingTop: 8,\n    paddingRight: 16,\n    paddingBottom: 8,\n    paddingLeft: 16,\n  },\n"));
+        assert!(code.contains("  'label': {\n    color: '#ffffff',\n    fontSize: 14,\n  },\n"));
+        assert_eq!(package.component_filename, "Button.tsx");
+    }
+
+    #[test]
+    fn root_text_layer_is_wrapped_in_text_with_its_text_styles() {
+        let root = element(
+            "2:1",
+            "Title",
+            &[("color", "#111111"), ("fontWeight", "700"), ("opacity", "0.5")],
+            Some("Hello"),
+            vec![],
+        );
+        let code = generate(&request("react-native"), &root).component_code;
+
+        assert!(code.contains("  <Text style={styles['title-text']}>{'Hello'}</Text>\n"));
+        assert!(code.contains("typeof children === 'string' ? <Text style={styles['title-text']}>{children}</Text> : children"));
+        assert!(code.contains("  'title': {\n    opacity: 0.5,\n  },\n"));
+        assert!(code.contains("  'title-text': {\n    color: '#111111',\n    fontWeight: '700',\n  },\n"));
+    }
+
+    #[test]
+    fn text_styles_are_dropped_from_views() {
+        let root = element(
+            "3:1",
+            "Card",
+            &[("background", "#ffffff"), ("color", "#333333")],
+            None,
+            vec![
+                element("3:2", "Card text", &[("color", "#000000"), ("width", "10px")], None, vec![]),
+                element("3:3", "Body", &[], None, vec![]),
+            ],
+        );
+        let code = generate(&request("react-native"), &root).component_code;
+
+        assert!(code.contains("  'card': {\n    backgroundColor: '#ffffff',\n  },\n"));
+        assert!(code.contains("  'card-text': {\n    width: 10,\n  },\n"));
+        // A layer already uses card-text, so the root's Text key is numbered
+        assert!(code.contains("  'card-text-2': {\n    color: '#333333',\n  },\n"));
+        assert!(code.contains("? <Text style={styles['card-text-2']}>{children}</Text>"));
+    }
+}
@@ -1,8 +1,17 @@
-use actix_web::{post, get, web, HttpResponse, Responder};
-use crate::generator::models::{CreateComponentRequest, CreateComponentResponse};
-use crate::generator::builder::generate_package_files;
+use actix_web::{post, get, delete, web, HttpResponse, Responder};
+use actix_web::http::StatusCode;
+use futures::{stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use crate::db::mongo::MongoDb;
+use crate::generator::models::{CreateComponentRequest, CreateComponentResponse, CreateComponentFromNodeRequest, JobListResponse, JobResponse, JobsParams};
+use crate::generator::builder::{generate_package_files, generate_package_files_from_node};
+use crate::generator::node_tree::{from_figma_node, component_name};
+use crate::figma::store::find_node;
 use crate::generator::filesystem::PackageBuilder;
-use crate::generator::docker::DockerBuilder;
+use crate::generator::docker::{DockerBuilder, LogStream};
+use crate::generator::jobs::{CancelOutcome, JobQueue, LogLine, DEFAULT_HISTORY_LIMIT, MAX_HISTORY_LIMIT};
+use crate::generator::sources;
+use crate::tokens::extract::{tokens_for_file, tokens_from_config, DEFAULT_MIN_OCCURRENCES};
 
 
 #[get("/build-docker-image")]
@@ -42,9 +51,52 @@ pub async fn build_docker_image() -> impl Responder {
 #[post("/create-component")]
 pub async fn create_component(
     req: web::Json<CreateComponentRequest>,
+    db: web::Data<MongoDb>,
+    jobs: web::Data<JobQueue>,
 ) -> impl Responder {
+    // Tokens come from a stored Figma file when one is named, otherwise
+    // from the config values themselves
+    let tokens = match &req.tokens_file_key {
+        Some(file_key) => match tokens_for_file(&db, file_key, DEFAULT_MIN_OCCURRENCES).await {
+            Ok(Some(tokens)) => tokens,
+            Ok(None) => {
+                return HttpResponse::NotFound().json(CreateComponentResponse {
+                    success: false,
+                    message: format!(
+                        "Figma file {} is not stored. Fetch it with /api/figma/getFile first",
+                        file_key
+                    ),
+                    job_id: None,
+                })
+            }
+            Err(e) => {
+                return HttpResponse::InternalServerError().json(CreateComponentResponse {
+                    success: false,
+                    message: format!("Database error: {}", e),
+                    job_id: None,
+                })
+            }
+        },
+        None => tokens_from_config(&req.name, &req.config),
+    };
+
+    // A requested theme replaces the default values of the tokens
+    let tokens = match &req.theme {
+        Some(theme) => match tokens.with_default_theme(theme) {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                return HttpResponse::BadRequest().json(CreateComponentResponse {
+                    success: false,
+                    message: e,
+                    job_id: None,
+                })
+            }
+        },
+        None => tokens,
+    };
+
     // Generate all package files
-    let package = match generate_package_files(&req) {
+    let package = match generate_package_files(&req, &tokens) {
         Ok(pkg) => pkg,
         Err(e) => {
             return HttpResponse::BadRequest().json(CreateComponentResponse {
@@ -76,71 +128,289 @@ pub async fn create_component(
         })
     }
     
-    let mut response_message = format!("Package created at: {:?}", builder.get_path());
-    
-    // Use Docker to build the package
-    let docker_builder = DockerBuilder::new();
-    
-    // Check Docker availability
-    match docker_builder.check_docker() {
-        Ok(_) => {
-            response_message.push_str("\n\nStarting Docker build process...");
-            
-            // Run npm install
-            response_message.push_str("\n1. Running npm install...");
-            match docker_builder.run_npm_install(builder.get_path()) {
-                Ok(_) => response_message.push_str(" ✓ Success"),
-                Err(e) => {
-                    response_message.push_str(&format!(" ✗ Failed: {}", e));
-                    return HttpResponse::Ok().json(CreateComponentResponse {
-                        success: true,
-                        message: response_message,
-                        job_id: Some(builder.job_id),
-                    });
-                }
-            }
-            
-            // Run npm build
-            response_message.push_str("\n2. Running npm build...");
-            match docker_builder.run_npm_build(builder.get_path()) {
-                Ok(_) => response_message.push_str(" ✓ Success"),
-                Err(e) => {
-                    response_message.push_str(&format!(" ✗ Failed: {}", e));
-                    return HttpResponse::Ok().json(CreateComponentResponse {
-                        success: true,
-                        message: response_message,
-                        job_id: Some(builder.job_id),
-                    });
-                }
-            }
-            
-            // Run npm pack
-            response_message.push_str("\n3. Creating npm package...");
-            match docker_builder.run_npm_pack(builder.get_path()) {
-                Ok(filename) => {
-                    response_message.push_str(&format!(" ✓ Success: {}", filename));
-                    response_message.push_str(&format!("\n\nPackage ready: {:?}/{}", builder.get_path(), filename));
-                }
-                Err(e) => {
-                    response_message.push_str(&format!(" ✗ Failed: {}", e));
-                }
-            }
-        }
-        Err(e) => {
-            response_message.push_str(&format!("\n\nDocker not available: {}", e));
-            response_message.push_str("\nPlease ensure Docker is installed and running");
-            response_message.push_str("\nThen call GET /api/generator/build-docker-image first");
+    let message = queued_message(&builder);
+    let job_id = jobs.enqueue(builder, &req.package_name, &*req).await;
+
+    HttpResponse::Accepted().json(CreateComponentResponse {
+        success: true,
+        message,
+        job_id: Some(job_id),
+    })
+}
+
+#[post("/create-component-from-node")]
+pub async fn create_component_from_node(
+    req: web::Json<CreateComponentFromNodeRequest>,
+    db: web::Data<crate::db::mongo::MongoDb>,
+    jobs: web::Data<JobQueue>,
+) -> impl Responder {
+    let builder = match prepare_package_from_node(&db, &req).await {
+        Ok(builder) => builder,
+        Err((status, message)) => {
+            return HttpResponse::build(status).json(CreateComponentResponse {
+                success: false,
+                message,
+                job_id: None,
+            })
         }
+    };
+
+    // Remembered so the package can be rebuilt when the file changes
+    if let Err(e) = sources::record(&db, &req, &builder.job_id).await {
+        eprintln!("Failed to record component source: {}", e);
     }
-    
-    HttpResponse::Ok().json(CreateComponentResponse {
+
+    let message = queued_message(&builder);
+    let job_id = jobs.enqueue(builder, &req.package_name, &*req).await;
+
+    HttpResponse::Accepted().json(CreateComponentResponse {
         success: true,
-        message: response_message,
-        job_id: Some(builder.job_id),
+        message,
+        job_id: Some(job_id),
     })
 }
 
+// GET handler listing stored build jobs, newest first, a page at a time
+#[get("/jobs")]
+pub async fn list_jobs(query: web::Query<JobsParams>, jobs: web::Data<JobQueue>) -> impl Responder {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_HISTORY_LIMIT)
+        .clamp(1, MAX_HISTORY_LIMIT);
+    match jobs.history(query.package_name.as_deref(), query.skip, limit).await {
+        Ok(history) => HttpResponse::Ok().json(JobListResponse {
+            success: true,
+            message: format!("Found {} build jobs", history.len()),
+            jobs: history,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(JobListResponse {
+            success: false,
+            message: format!("Database error: {}", e),
+            jobs: Vec::new(),
+        }),
+    }
+}
+
+// GET handler reporting a build job's state and step timings
+#[get("/jobs/{job_id}")]
+pub async fn get_job(path: web::Path<String>, jobs: web::Data<JobQueue>) -> impl Responder {
+    let job_id = path.into_inner();
+    match jobs.get(&job_id).await {
+        Ok(Some(job)) => HttpResponse::Ok().json(JobResponse {
+            success: true,
+            message: format!("Build job {} is {}", job_id, job.status.as_str()),
+            job: Some(job),
+        }),
+        Ok(None) => HttpResponse::NotFound().json(JobResponse {
+            success: false,
+            message: format!("Build job {} not found", job_id),
+            job: None,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(JobResponse {
+            success: false,
+            message: format!("Database error: {}", e),
+            job: None,
+        }),
+    }
+}
+
+// DELETE handler cancelling a queued or running build job
+#[delete("/jobs/{job_id}")]
+pub async fn cancel_job(path: web::Path<String>, jobs: web::Data<JobQueue>) -> impl Responder {
+    let job_id = path.into_inner();
+    match jobs.cancel(&job_id).await {
+        Ok(CancelOutcome::Requested(job)) => HttpResponse::Accepted().json(JobResponse {
+            success: true,
+            message: format!("Cancelling build job {}", job_id),
+            job: Some(job),
+        }),
+        Ok(CancelOutcome::Finished(job)) => HttpResponse::Conflict().json(JobResponse {
+            success: false,
+            message: format!("Build job {} already {}", job_id, job.status.as_str()),
+            job: Some(job),
+        }),
+        Ok(CancelOutcome::NotFound) => HttpResponse::NotFound().json(JobResponse {
+            success: false,
+            message: format!("Build job {} not found", job_id),
+            job: None,
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(JobResponse {
+            success: false,
+            message: format!("Database error: {}", e),
+            job: None,
+        }),
+    }
+}
+
+// GET handler streaming a job's npm output as Server-Sent Events. Earlier
+// lines are replayed first; an "end" event carries the final status.
+#[get("/jobs/{job_id}/logs")]
+pub async fn stream_job_logs(path: web::Path<String>, jobs: web::Data<JobQueue>) -> impl Responder {
+    let job_id = path.into_inner();
+    let follow = match jobs.follow_logs(&job_id).await {
+        Ok(Some(follow)) => follow,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(JobResponse {
+                success: false,
+                message: format!("Build job {} not found", job_id),
+                job: None,
+            })
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(JobResponse {
+                success: false,
+                message: format!("Database error: {}", e),
+                job: None,
+            })
+        }
+    };
+
+    let backlog = stream::iter(follow.backlog).map(|line| log_event(&line));
+    let live = stream::unfold(follow.live, |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(line) => return Some((log_event(&line), Some(receiver))),
+                // Lines dropped for a slow client are still in the job record
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    let end = stream::once(async move {
+        let job = jobs.get(&job_id).await.ok().flatten();
+        let data = serde_json::json!({
+            "status": job.as_ref().map(|job| job.status.as_str()),
+            "error": job.and_then(|job| job.error),
+        });
+        sse_event("end", &data.to_string())
+    });
+    let events = backlog
+        .chain(live)
+        .chain(end)
+        .map(Ok::<_, actix_web::Error>);
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events)
+}
+
+fn log_event(line: &LogLine) -> web::Bytes {
+    let data = serde_json::to_string(line).unwrap_or_default();
+    let event = match line.stream {
+        LogStream::Stdout => "stdout",
+        LogStream::Stderr => "stderr",
+    };
+    sse_event(event, &data)
+}
+
+// Single-line data only, which holds for serialized JSON
+fn sse_event(event: &str, data: &str) -> web::Bytes {
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
+fn queued_message(builder: &PackageBuilder) -> String {
+    format!(
+        "Package created at: {:?}. Build queued, follow it at /api/generator/jobs/{}",
+        builder.get_path(),
+        builder.job_id
+    )
+}
+
+// Load a stored node, convert it and write the package files to disk
+async fn prepare_package_from_node(
+    db: &MongoDb,
+    req: &CreateComponentFromNodeRequest,
+) -> Result<PackageBuilder, (StatusCode, String)> {
+    // Load the node JSON stored by getNode / getFile
+    let node = match find_node(db, &req.file_key, &req.node_id).await {
+        Ok(Some(node)) => node,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                format!(
+                    "Node {} not found for file {}. Fetch it with /api/figma/getNode first",
+                    req.node_id, req.file_key
+                ),
+            ))
+        }
+        Err(e) => return Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e))),
+    };
+
+    let root = from_figma_node(&node).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Node {} is hidden and produces no output", req.node_id),
+        )
+    })?;
+
+    let name = req.name.clone().unwrap_or_else(|| component_name(&root.name));
+    let component_request = req.to_component_request(name);
+
+    // A node can be stored without its file, in which case there are no tokens
+    let tokens = tokens_for_file(db, &req.file_key, DEFAULT_MIN_OCCURRENCES)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Database error: {}", e)))?
+        .unwrap_or_default();
+    let tokens = match &req.theme {
+        Some(theme) => tokens.with_default_theme(theme).map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+        None => tokens,
+    };
+
+    let package = generate_package_files_from_node(&component_request, &root, &tokens)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let builder = PackageBuilder::new().map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create build directory: {}", e),
+        )
+    })?;
+
+    builder
+        .write_package(&component_request, &package)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write files: {}", e)))?;
+
+    Ok(builder)
+}
+
+// Rebuild every package generated from nodes of a file, e.g. after a
+// webhook reported a change. Returns how many builds were queued.
+pub async fn regenerate_file(
+    db: &MongoDb,
+    jobs: &JobQueue,
+    file_key: &str,
+) -> Result<usize, mongodb::error::Error> {
+    let sources = sources::for_file(db, file_key).await?;
+
+    let mut started = 0;
+    for source in sources {
+        let request = source.request;
+        let builder = match prepare_package_from_node(db, &request).await {
+            Ok(builder) => builder,
+            Err((_, message)) => {
+                eprintln!("Skipping regeneration of {}: {}", request.package_name, message);
+                continue;
+            }
+        };
+        if let Err(e) = sources::record(db, &request, &builder.job_id).await {
+            eprintln!("Failed to record component source: {}", e);
+        }
+        started += 1;
+
+        let job_id = jobs.enqueue(builder, &request.package_name, &request).await;
+        println!("Queued regeneration of {} as job {}", request.package_name, job_id);
+    }
+    Ok(started)
+}
+
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(create_component)
+       .service(create_component_from_node)
+       .service(list_jobs)
+       .service(get_job)
+       .service(stream_job_logs)
+       .service(cancel_job)
        .service(build_docker_image);
 }
\ No newline at end of file
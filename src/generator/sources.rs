@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use crate::db::mongo::MongoDb;
+use crate::figma::store::normalize_node_id;
+use crate::generator::models::CreateComponentFromNodeRequest;
+
+// Documents stored in the component_sources collection: one per package
+// generated from a Figma node, so it can be rebuilt when the file changes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentSource {
+    #[serde(flatten)]
+    pub request: CreateComponentFromNodeRequest,
+    pub last_job_id: Option<String>,
+    #[serde(
+        default,
+        with = "bson::serde_helpers::chrono_datetime_as_bson_datetime_optional"
+    )]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// Remember the request that produced a package
+pub async fn record(
+    db: &MongoDb,
+    request: &CreateComponentFromNodeRequest,
+    job_id: &str,
+) -> Result<(), mongodb::error::Error> {
+    let node_id = normalize_node_id(&request.node_id);
+    let filter = doc! {
+        "file_key": &request.file_key,
+        "node_id": &node_id,
+        "package_name": &request.package_name,
+    };
+    let source = ComponentSource {
+        request: CreateComponentFromNodeRequest {
+            node_id,
+            ..request.clone()
+        },
+        last_job_id: Some(job_id.to_string()),
+        updated_at: Some(Utc::now()),
+    };
+    db.replace_in_collection("component_sources", filter, &source).await
+}
+
+// Every package generated from nodes of this file
+pub async fn for_file(
+    db: &MongoDb,
+    file_key: &str,
+) -> Result<Vec<ComponentSource>, mongodb::error::Error> {
+    db.get_documents_from_collection("component_sources", doc! { "file_key": file_key })
+        .await
+}
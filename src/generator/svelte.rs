@@ -0,0 +1,209 @@
+// Svelte output: a .svelte component with typed props and scoped styles,
+// packaged with svelte-package
+use tera::{Context, Tera};
+use crate::generator::builder::{GeneratedPackage, GeneratedFile};
+use crate::generator::framework::{Framework, Styling};
+use crate::generator::markup::{class_names, render_stylesheet, render_template, Dialect};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::ElementNode;
+use crate::tokens::css::render_files;
+use crate::tokens::model::TokenSet;
+
+pub fn add_templates(tera: &mut Tera) {
+    tera.add_raw_template("svelte_component", r##"<script{% if typescript %} lang="ts"{% endif %}>
+  import { createEventDispatcher } from 'svelte';
+
+{%- if typescript %}
+
+  export let disabled: boolean = false;
+
+  const dispatch = createEventDispatcher<{ click: MouseEvent }>();
+
+  function onClick(event: MouseEvent) {
+{%- else %}
+
+  export let disabled = false;
+
+  const dispatch = createEventDispatcher();
+
+  function onClick(event) {
+{%- endif %}
+    if (!disabled) {
+      dispatch('click', event);
+    }
+  }
+</script>
+
+{{ markup }}
+<style>
+{{ css }}</style>
+"##).expect("Failed to add svelte_component template");
+
+    tera.add_raw_template("svelte_package_json", r##"{
+  "name": "{{ package_name }}",
+  "version": "{{ version }}",
+  "description": "{{ name }} component",
+  "type": "module",
+  "svelte": "./dist/index.js",
+  "types": "./dist/index.d.ts",
+  "exports": {
+    ".": {
+      "types": "./dist/index.d.ts",
+      "svelte": "./dist/index.js",
+      "default": "./dist/index.js"
+    },
+    "./styles/*": "./styles/*"
+  },
+  "style": "styles/tokens.css",
+  "files": [
+    "dist",
+    "styles"
+  ],
+  "scripts": {
+    "build": "svelte-package --input src --output dist",
+    "prepublishOnly": "npm run build"
+  },
+  "peerDependencies": {
+    "svelte": "^4.0.0 || ^5.0.0"
+  },
+  "devDependencies": {
+    "@sveltejs/package": "^2.2.0",
+    "@sveltejs/vite-plugin-svelte": "^3.0.0",
+    "svelte": "^4.2.0",
+    "typescript": "^5.0.0",
+    "vite": "^5.0.0"
+  },
+  "license": "MIT"
+}
+"##).expect("Failed to add svelte_package_json template");
+}
+
+// svelte-package runs <script lang="ts"> through this preprocessor
+const SVELTE_CONFIG: &str = r#"import { vitePreprocess } from '@sveltejs/vite-plugin-svelte';
+
+export default {
+  preprocess: vitePreprocess(),
+};
+"#;
+
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2020",
+    "module": "ESNext",
+    "moduleResolution": "bundler",
+    "lib": ["ES2020", "DOM"],
+    "strict": true,
+    "declaration": true,
+    "isolatedModules": true,
+    "verbatimModuleSyntax": true,
+    "skipLibCheck": true
+  },
+  "include": ["src"],
+  "exclude": ["node_modules", "dist"]
+}
+"#;
+
+fn dialect() -> Dialect {
+    Dialect {
+        root_attributes: vec![
+            "aria-disabled={disabled || undefined}".to_string(),
+            "on:click={onClick}".to_string(),
+        ],
+        children: "<slot />".to_string(),
+        interpolate: |literal| format!("{{{}}}", literal),
+    }
+}
+
+pub fn generate(
+    tera: &Tera,
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    let classes = class_names(root);
+
+    let mut context = Context::new();
+    context.insert("name", &request.name);
+    context.insert("typescript", &request.typescript);
+    context.insert("package_name", &request.package_name);
+    context.insert("version", &request.version);
+    context.insert("markup", &render_template(root, &classes, &dialect(), 0));
+    context.insert("css", &render_stylesheet(root, &classes));
+
+    let component_code = tera.render("svelte_component", &context)
+        .map_err(|e| format!("Component template error: {}", e))?;
+
+    let package_json = tera.render("svelte_package_json", &context)
+        .map_err(|e| format!("Package.json template error: {}", e))?;
+
+    // svelte-package emits ES modules, so relative imports keep their extension
+    let framework = Framework::Svelte;
+    let component_filename = framework.component_filename(&request.name, request.typescript);
+    let index_ts = format!(
+        "export {{ default as {} }} from './{}';\nexport * from './tokens.js';\nexport * from './themes.js';\n",
+        request.name, component_filename
+    );
+
+    Ok(GeneratedPackage {
+        framework,
+        styling: Styling::Inline,
+        component_filename,
+        component_code,
+        package_json,
+        index_ts,
+        tokens: render_files(tokens),
+        extra_files: vec![
+            GeneratedFile::new("svelte.config.js", SVELTE_CONFIG),
+            GeneratedFile::new("tsconfig.json", TSCONFIG),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::test_support::{button, element, generate, request};
+
+    #[test]
+    fn layers_become_markup_with_scoped_class_rules() {
+        let package = generate(&request("svelte"), &button());
+        let code = &package.component_code;
+
+        assert!(code.starts_with("<script lang=\"ts\">\n  import { createEventDispatcher } from 'svelte';\n\n  export let disabled: boolean = false;\n"));
+        assert!(code.contains(
+            "</script>\n\n<div\n  class=\"button\"\n  data-figma-id=\"1:1\"\n  aria-disabled={disabled || undefined}\n  \
+             on:click={onClick}\n>\n  <span\n    class=\"label\"\n    data-figma-id=\"1:2\"\n  >\n    {'Click me'}\n  </span>\n  \
+             <slot />\n</div>\n\n<style>\n.button {\n"
+        ));
+        assert!(code.ends_with(".label {\n  color: #ffffff;\n  font-size: 14px;\n}\n</style>\n"));
+        assert_eq!(package.component_filename, "Button.svelte");
+        // svelte-package output is ES modules, so imports keep their extension
+        assert!(package.index_ts.contains("export * from './tokens.js';\n"));
+    }
+
+    #[test]
+    fn javascript_components_use_an_untyped_dispatcher() {
+        let mut request = request("svelte");
+        request.typescript = false;
+        let code = generate(&request, &button()).component_code;
+
+        assert!(code.starts_with("<script>\n"));
+        assert!(code.contains("  export let disabled = false;\n\n  const dispatch = createEventDispatcher();\n\n  function onClick(event) {\n"));
+    }
+
+    #[test]
+    fn text_cannot_open_a_svelte_expression() {
+        let root = element("1:1", "Note", &[], Some("{@html x}"), vec![]);
+        let code = generate(&request("svelte"), &root).component_code;
+        assert!(code.contains("  {'\\u007b@html x\\u007d'}\n"));
+    }
+
+    #[test]
+    fn the_package_is_built_with_svelte_package() {
+        let package = generate(&request("svelte"), &button());
+        let package_json: serde_json::Value = serde_json::from_str(&package.package_json).unwrap();
+        assert_eq!(package_json["scripts"]["build"], "svelte-package --input src --output dist");
+        assert_eq!(package_json["exports"]["."]["svelte"], "./dist/index.js");
+        let paths: Vec<&str> = package.extra_files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["svelte.config.js", "tsconfig.json"]);
+    }
+}
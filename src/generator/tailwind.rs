@@ -0,0 +1,521 @@
+// Tailwind styling: utility classes for each element's styles, and a preset
+// that exposes the design tokens as theme values. Values that use a token
+// get the token's class, values on the theme scale get the scale class, and
+// everything else an arbitrary value. The scale is Tailwind's default
+// unless the request passes the consumer's own.
+use std::collections::BTreeMap;
+use crate::generator::models::TailwindScales;
+use crate::generator::markup::{box_sides, css_property};
+use crate::tokens::css::{css_var, HEADER};
+use crate::tokens::model::{TokenSet, TokenValue};
+
+// Default spacing scale, also used for sizes and insets
+const SPACING: &[(&str, &str)] = &[
+    ("0", "0"), ("1px", "px"), ("2px", "0.5"), ("4px", "1"), ("6px", "1.5"), ("8px", "2"),
+    ("10px", "2.5"), ("12px", "3"), ("14px", "3.5"), ("16px", "4"), ("20px", "5"), ("24px", "6"),
+    ("28px", "7"), ("32px", "8"), ("36px", "9"), ("40px", "10"), ("44px", "11"), ("48px", "12"),
+    ("56px", "14"), ("64px", "16"), ("80px", "20"), ("96px", "24"), ("112px", "28"), ("128px", "32"),
+    ("144px", "36"), ("160px", "40"), ("176px", "44"), ("192px", "48"), ("208px", "52"),
+    ("224px", "56"), ("240px", "60"), ("256px", "64"), ("288px", "72"), ("320px", "80"),
+    ("384px", "96"),
+];
+
+// An empty key is the bare utility, e.g. "rounded"
+const RADIUS: &[(&str, &str)] = &[
+    ("0", "none"), ("2px", "sm"), ("4px", ""), ("6px", "md"), ("8px", "lg"), ("12px", "xl"),
+    ("16px", "2xl"), ("24px", "3xl"), ("9999px", "full"), ("50%", "full"),
+];
+
+const FONT_SIZE: &[(&str, &str)] = &[
+    ("12px", "xs"), ("14px", "sm"), ("16px", "base"), ("18px", "lg"), ("20px", "xl"), ("24px", "2xl"),
+    ("30px", "3xl"), ("36px", "4xl"), ("48px", "5xl"), ("60px", "6xl"), ("72px", "7xl"),
+    ("96px", "8xl"), ("128px", "9xl"),
+];
+
+const LINE_HEIGHT: &[(&str, &str)] = &[
+    ("12px", "3"), ("16px", "4"), ("20px", "5"), ("24px", "6"), ("28px", "7"), ("32px", "8"),
+    ("36px", "9"), ("40px", "10"),
+];
+
+const FONT_WEIGHT: &[(&str, &str)] = &[
+    ("100", "thin"), ("200", "extralight"), ("300", "light"), ("400", "normal"), ("500", "medium"),
+    ("600", "semibold"), ("700", "bold"), ("800", "extrabold"), ("900", "black"),
+];
+
+const BORDER_WIDTH: &[(&str, &str)] = &[("0", "0"), ("1px", ""), ("2px", "2"), ("4px", "4"), ("8px", "8")];
+
+const COLORS: &[(&str, &str)] = &[("#ffffff", "white"), ("#000000", "black"), ("transparent", "transparent")];
+
+// Theme sections a request can give a scale for
+const SCALE_SECTIONS: &[&str] = &[
+    "spacing", "borderRadius", "fontSize", "lineHeight", "fontWeight", "borderWidth", "colors",
+    "letterSpacing", "boxShadow",
+];
+
+// (property, value, class) for keyword values
+const KEYWORDS: &[(&str, &str, &str)] = &[
+    ("display", "flex", "flex"),
+    ("display", "inline-flex", "inline-flex"),
+    ("display", "block", "block"),
+    ("display", "inline-block", "inline-block"),
+    ("display", "grid", "grid"),
+    ("display", "none", "hidden"),
+    ("flexDirection", "row", "flex-row"),
+    ("flexDirection", "column", "flex-col"),
+    ("flexDirection", "row-reverse", "flex-row-reverse"),
+    ("flexDirection", "column-reverse", "flex-col-reverse"),
+    ("flexWrap", "wrap", "flex-wrap"),
+    ("flexWrap", "nowrap", "flex-nowrap"),
+    ("justifyContent", "flex-start", "justify-start"),
+    ("justifyContent", "flex-end", "justify-end"),
+    ("justifyContent", "center", "justify-center"),
+    ("justifyContent", "space-between", "justify-between"),
+    ("justifyContent", "space-around", "justify-around"),
+    ("justifyContent", "space-evenly", "justify-evenly"),
+    ("alignItems", "flex-start", "items-start"),
+    ("alignItems", "flex-end", "items-end"),
+    ("alignItems", "center", "items-center"),
+    ("alignItems", "baseline", "items-baseline"),
+    ("alignItems", "stretch", "items-stretch"),
+    ("alignContent", "flex-start", "content-start"),
+    ("alignContent", "flex-end", "content-end"),
+    ("alignContent", "center", "content-center"),
+    ("alignContent", "space-between", "content-between"),
+    ("alignSelf", "auto", "self-auto"),
+    ("alignSelf", "flex-start", "self-start"),
+    ("alignSelf", "flex-end", "self-end"),
+    ("alignSelf", "center", "self-center"),
+    ("alignSelf", "stretch", "self-stretch"),
+    ("flexGrow", "1", "grow"),
+    ("flexGrow", "0", "grow-0"),
+    ("flexShrink", "1", "shrink"),
+    ("flexShrink", "0", "shrink-0"),
+    ("flexBasis", "0", "basis-0"),
+    ("position", "static", "static"),
+    ("position", "relative", "relative"),
+    ("position", "absolute", "absolute"),
+    ("position", "fixed", "fixed"),
+    ("position", "sticky", "sticky"),
+    ("overflow", "hidden", "overflow-hidden"),
+    ("overflow", "auto", "overflow-auto"),
+    ("overflow", "scroll", "overflow-scroll"),
+    ("overflow", "visible", "overflow-visible"),
+    ("boxSizing", "border-box", "box-border"),
+    ("boxSizing", "content-box", "box-content"),
+    ("cursor", "pointer", "cursor-pointer"),
+    ("cursor", "default", "cursor-default"),
+    ("textAlign", "left", "text-left"),
+    ("textAlign", "center", "text-center"),
+    ("textAlign", "right", "text-right"),
+    ("textAlign", "justify", "text-justify"),
+    ("textDecoration", "underline", "underline"),
+    ("textDecoration", "line-through", "line-through"),
+    ("textDecoration", "none", "no-underline"),
+    ("textTransform", "uppercase", "uppercase"),
+    ("textTransform", "lowercase", "lowercase"),
+    ("textTransform", "capitalize", "capitalize"),
+    ("textTransform", "none", "normal-case"),
+    ("fontStyle", "italic", "italic"),
+    ("fontStyle", "normal", "not-italic"),
+];
+
+// Token values exposed to Tailwind, keyed like the tokens without their
+// category: --color-brand-primary is colors["brand-primary"], i.e. bg-brand-primary
+pub struct TailwindTheme {
+    // (theme section, key, custom property) in token order
+    entries: Vec<(&'static str, String, String)>,
+    // Configured scales as (value, key) per section, replacing the defaults
+    scales: BTreeMap<String, Vec<(String, String)>>,
+}
+
+impl TailwindTheme {
+    pub fn new(set: &TokenSet) -> Self {
+        let mut entries: Vec<(&'static str, String, String)> = Vec::new();
+        let mut add = |section: &'static str, key: String, name: String| {
+            if !entries.iter().any(|(s, k, _)| *s == section && *k == key) {
+                entries.push((section, key, name));
+            }
+        };
+
+        for token in &set.tokens {
+            let Some(value) = set.resolve(&token.value) else {
+                continue;
+            };
+            let key = if token.path.len() > 1 { token.path[1..].join("-") } else { token.path.join("-") };
+            let name = css_var(&token.path);
+
+            if let TokenValue::Typography(typography) = value {
+                let fields = [
+                    (typography.font_family.is_some(), "font-family", "fontFamily"),
+                    (typography.font_size.is_some(), "font-size", "fontSize"),
+                    (typography.font_weight.is_some(), "font-weight", "fontWeight"),
+                    (typography.line_height.is_some(), "line-height", "lineHeight"),
+                    (typography.letter_spacing.is_some(), "letter-spacing", "letterSpacing"),
+                ];
+                for (_, field, section) in fields.into_iter().filter(|(present, _, _)| *present) {
+                    add(section, key.clone(), format!("{}-{}", name, field));
+                }
+                continue;
+            }
+
+            let section = match (token.path[0].as_str(), value) {
+                (_, TokenValue::Color(_)) => "colors",
+                (_, TokenValue::Shadow(_)) => "boxShadow",
+                ("radius", _) => "borderRadius",
+                ("spacing", _) => "spacing",
+                ("font-size", _) => "fontSize",
+                ("line-height", _) => "lineHeight",
+                ("letter-spacing", _) => "letterSpacing",
+                ("font-family", _) | (_, TokenValue::FontFamily(_)) => "fontFamily",
+                ("font-weight", _) => "fontWeight",
+                ("opacity", _) => "opacity",
+                ("border-width", _) => "borderWidth",
+                _ => continue,
+            };
+            add(section, key, name);
+        }
+        TailwindTheme { entries, scales: BTreeMap::new() }
+    }
+
+    // Snap to the consumer's theme scales. They are not written to the
+    // preset, the consumer's config already has them.
+    pub fn with_scales(mut self, scales: &TailwindScales) -> Result<Self, String> {
+        for (section, values) in scales {
+            if !SCALE_SECTIONS.contains(&section.as_str()) {
+                return Err(format!(
+                    "Unknown tailwind_theme section: {}. Supported sections: {}",
+                    section,
+                    SCALE_SECTIONS.join(", ")
+                ));
+            }
+            let scale = values
+                .iter()
+                .map(|(key, value)| {
+                    // DEFAULT is the bare utility, e.g. "rounded"
+                    let key = if key == "DEFAULT" { String::new() } else { key.clone() };
+                    (value.trim().to_string(), key)
+                })
+                .collect();
+            self.scales.insert(section.clone(), scale);
+        }
+        Ok(self)
+    }
+
+    // tailwind.preset.js: CommonJS so every Tailwind config can require it.
+    // Values are custom properties so the themes in tokens.css still apply.
+    pub fn preset(&self, package_name: &str) -> String {
+        let mut sections: Vec<&str> = Vec::new();
+        for (section, _, _) in &self.entries {
+            if !sections.contains(section) {
+                sections.push(section);
+            }
+        }
+
+        let mut out = format!("// {}\n", HEADER);
+        out.push_str("// Add to `presets` in tailwind.config.js, next to styles/tokens.css.\n");
+        out.push_str("// Tailwind does not take `content` from presets, so also add\n");
+        out.push_str(&format!("// {} to your own `content`.\n\n", content_glob(package_name)));
+        out.push_str("/** @type {import('tailwindcss').Config} */\n");
+        out.push_str("module.exports = {\n");
+        out.push_str("  theme: {\n    extend: {\n");
+        for section in sections {
+            out.push_str(&format!("      {}: {{\n", section));
+            for (_, key, name) in self.entries.iter().filter(|(s, _, _)| *s == section) {
+                out.push_str(&format!("        '{}': 'var({})',\n", key, name));
+            }
+            out.push_str("      },\n");
+        }
+        out.push_str("    },\n  },\n};\n");
+        out
+    }
+
+    // Utility classes for an element's styles
+    pub fn classes(&self, styles: &BTreeMap<String, String>) -> Vec<String> {
+        let mut out = Vec::new();
+        for (property, value) in styles {
+            let value = value.trim();
+            if let Some((_, _, class)) = KEYWORDS.iter().find(|(p, v, _)| p == property && *v == value) {
+                out.push(class.to_string());
+                continue;
+            }
+            match property.as_str() {
+                "background" | "backgroundColor" => out.push(self.utility("bg", "colors", value, COLORS)),
+                "color" => out.push(self.utility("text", "colors", value, COLORS)),
+                "borderColor" => out.push(self.utility("border", "colors", value, COLORS)),
+                "border" => {
+                    let mut parts = value.splitn(3, ' ');
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some(width), Some(style), Some(color)) => {
+                            out.push(self.utility("border", "borderWidth", width, BORDER_WIDTH));
+                            out.push(format!("border-{}", style));
+                            out.push(self.utility("border", "colors", color, COLORS));
+                        }
+                        _ => out.push(arbitrary_property(property, value)),
+                    }
+                }
+                "borderWidth" => out.push(self.utility("border", "borderWidth", value, BORDER_WIDTH)),
+                "borderRadius" => {
+                    let radii: Vec<&str> = if token_reference(value).is_some() {
+                        vec![value]
+                    } else {
+                        value.split_whitespace().collect()
+                    };
+                    if let [top_left, top_right, bottom_right, bottom_left] = radii.as_slice()
+                        && !(top_left == top_right && top_right == bottom_right && bottom_right == bottom_left)
+                    {
+                        for (prefix, radius) in [
+                            ("rounded-tl", top_left),
+                            ("rounded-tr", top_right),
+                            ("rounded-br", bottom_right),
+                            ("rounded-bl", bottom_left),
+                        ] {
+                            out.push(self.utility(prefix, "borderRadius", radius, RADIUS));
+                        }
+                    } else {
+                        let radius = radii.first().copied().unwrap_or(value);
+                        out.push(self.utility("rounded", "borderRadius", radius, RADIUS));
+                    }
+                }
+                "padding" | "margin" => {
+                    let prefix = if property == "padding" { "p" } else { "m" };
+                    let sides = if token_reference(value).is_some() { Vec::new() } else { box_sides(value) };
+                    match sides.first() {
+                        None => out.push(self.utility(prefix, "spacing", value, SPACING)),
+                        Some((_, first)) if sides.iter().all(|(_, side)| side == first) => {
+                            out.push(self.utility(prefix, "spacing", first, SPACING))
+                        }
+                        // Symmetric shorthand, e.g. "8px 16px"
+                        Some((_, top)) if sides[2].1 == *top && sides[1].1 == sides[3].1 => {
+                            out.push(self.utility(&format!("{}y", prefix), "spacing", top, SPACING));
+                            out.push(self.utility(&format!("{}x", prefix), "spacing", sides[1].1, SPACING));
+                        }
+                        Some(_) => {
+                            for (side, side_value) in sides {
+                                let side_prefix = format!("{}{}", prefix, side[..1].to_ascii_lowercase());
+                                out.push(self.utility(&side_prefix, "spacing", side_value, SPACING));
+                            }
+                        }
+                    }
+                }
+                "paddingTop" => out.push(self.utility("pt", "spacing", value, SPACING)),
+                "paddingRight" => out.push(self.utility("pr", "spacing", value, SPACING)),
+                "paddingBottom" => out.push(self.utility("pb", "spacing", value, SPACING)),
+                "paddingLeft" => out.push(self.utility("pl", "spacing", value, SPACING)),
+                "marginTop" => out.push(self.utility("mt", "spacing", value, SPACING)),
+                "marginRight" => out.push(self.utility("mr", "spacing", value, SPACING)),
+                "marginBottom" => out.push(self.utility("mb", "spacing", value, SPACING)),
+                "marginLeft" => out.push(self.utility("ml", "spacing", value, SPACING)),
+                "gap" => out.push(self.utility("gap", "spacing", value, SPACING)),
+                "rowGap" => out.push(self.utility("gap-y", "spacing", value, SPACING)),
+                "columnGap" => out.push(self.utility("gap-x", "spacing", value, SPACING)),
+                "width" | "height" if value == "100%" => out.push(format!("{}-full", &property[..1])),
+                "width" => out.push(self.utility("w", "spacing", value, SPACING)),
+                "height" => out.push(self.utility("h", "spacing", value, SPACING)),
+                "top" | "right" | "bottom" | "left" => out.push(self.utility(property, "spacing", value, SPACING)),
+                "fontSize" => out.push(self.utility("text", "fontSize", value, FONT_SIZE)),
+                "lineHeight" => out.push(self.utility("leading", "lineHeight", value, LINE_HEIGHT)),
+                "letterSpacing" => out.push(self.utility("tracking", "letterSpacing", value, &[])),
+                "fontWeight" => out.push(self.utility("font", "fontWeight", value, FONT_WEIGHT)),
+                "fontFamily" => match self.token_class("font", "fontFamily", value) {
+                    Some(class) => out.push(class),
+                    None => out.push(format!("font-['{}']", arbitrary(&literal(value).replace(['"', '\''], "")))),
+                },
+                "boxShadow" => out.push(self.utility("shadow", "boxShadow", value, &[])),
+                "opacity" => out.push(self.opacity(value)),
+                _ => out.push(arbitrary_property(property, value)),
+            }
+        }
+        out
+    }
+
+    // Token class, then the theme scale, then an arbitrary value
+    fn utility(&self, prefix: &str, section: &str, value: &str, defaults: &[(&str, &str)]) -> String {
+        if let Some(class) = self.token_class(prefix, section, value) {
+            return class;
+        }
+        let literal = literal(value);
+        let normalized = zero(literal);
+        let key = match self.scales.get(section) {
+            Some(scale) => scale
+                .iter()
+                .find(|(v, _)| zero(v).eq_ignore_ascii_case(normalized))
+                .map(|(_, key)| key.as_str()),
+            None => defaults
+                .iter()
+                .find(|(v, _)| v.eq_ignore_ascii_case(normalized))
+                .map(|(_, key)| *key),
+        };
+        match key {
+            Some("") => prefix.to_string(),
+            Some(key) => format!("{}-{}", prefix, key),
+            None => format!("{}-[{}]", prefix, arbitrary(literal)),
+        }
+    }
+
+    // Values written as var(--token, literal) by the token lookup
+    fn token_class(&self, prefix: &str, section: &str, value: &str) -> Option<String> {
+        let (name, _) = token_reference(value)?;
+        let (_, key, _) = self.entries.iter().find(|(s, _, n)| *s == section && n == name)?;
+        Some(format!("{}-{}", prefix, key))
+    }
+
+    // Opacity utilities are percentages in steps of five
+    fn opacity(&self, value: &str) -> String {
+        if let Some(class) = self.token_class("opacity", "opacity", value) {
+            return class;
+        }
+        let literal = literal(value);
+        match literal.parse::<f64>() {
+            Ok(opacity) if ((opacity * 100.0).round() as i64) % 5 == 0 => {
+                format!("opacity-{}", (opacity * 100.0).round() as i64)
+            }
+            _ => format!("opacity-[{}]", arbitrary(literal)),
+        }
+    }
+}
+
+// "var(--color-primary, #ff0000)" is ("--color-primary", "#ff0000")
+fn token_reference(value: &str) -> Option<(&str, &str)> {
+    let inner = value.strip_prefix("var(")?.strip_suffix(')')?;
+    inner.split_once(", ")
+}
+
+// The glob consumers add to their Tailwind `content` for this package
+pub fn content_glob(package_name: &str) -> String {
+    format!("'./node_modules/{}/dist/**/*.js'", package_name)
+}
+
+// Setup notes shipped with a Tailwind-styled package
+pub fn readme(package_name: &str) -> String {
+    let mut out = format!("# {}\n\n", package_name);
+    out.push_str("The components are styled with Tailwind utility classes, so Tailwind must\n");
+    out.push_str("scan this package. Tailwind does not take `content` from presets: add the\n");
+    out.push_str("package's build output to the `content` of your own `tailwind.config.js`,\n");
+    out.push_str("next to the preset with the design tokens.\n\n");
+    out.push_str("```js\nmodule.exports = {\n");
+    out.push_str(&format!("  presets: [require('{}/tailwind.preset.js')],\n", package_name));
+    out.push_str(&format!("  content: ['./src/**/*.{{js,jsx,ts,tsx}}', {}],\n", content_glob(package_name)));
+    out.push_str("};\n```\n\n");
+    out.push_str(&format!(
+        "The token values are custom properties, so import `{}/styles/tokens.css` once as well.\n",
+        package_name
+    ));
+    out
+}
+
+fn zero(value: &str) -> &str {
+    if value == "0px" { "0" } else { value }
+}
+
+fn literal(value: &str) -> &str {
+    token_reference(value).map(|(_, literal)| literal).unwrap_or(value)
+}
+
+// Arbitrary values can't contain spaces: "rgba(0, 0, 0, 0.5)" is "rgba(0,0,0,0.5)"
+fn arbitrary(value: &str) -> String {
+    value.replace(", ", ",").replace(' ', "_")
+}
+
+fn arbitrary_property(property: &str, value: &str) -> String {
+    format!("[{}:{}]", css_property(property), arbitrary(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::figma::models::Color;
+    use crate::tokens::model::{Token, TokenSource, TokenType};
+
+    fn styles(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(p, v)| (p.to_string(), v.to_string())).collect()
+    }
+
+    fn tokens() -> TokenSet {
+        TokenSet {
+            tokens: vec![Token {
+                path: vec!["color".to_string(), "brand".to_string(), "primary".to_string()],
+                token_type: TokenType::Color,
+                value: TokenValue::Color(Color { r: 0.0, g: 0.0, b: 1.0, a: 1.0 }),
+                description: None,
+                modes: Vec::new(),
+                default_mode: None,
+                source: TokenSource::Variable,
+                occurrences: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn values_on_the_default_scale_get_scale_classes() {
+        let theme = TailwindTheme::new(&TokenSet::default());
+        let classes = theme.classes(&styles(&[
+            ("display", "flex"),
+            ("flexDirection", "column"),
+            ("padding", "8px 16px"),
+            ("gap", "12px"),
+            ("borderRadius", "4px"),
+            ("fontSize", "14px"),
+            ("fontWeight", "600"),
+            ("background", "#ffffff"),
+            ("width", "100%"),
+            ("opacity", "0.5"),
+        ]));
+        assert_eq!(
+            classes,
+            [
+                "bg-white", "rounded", "flex", "flex-col", "text-sm", "font-semibold", "gap-3",
+                "opacity-50", "py-2", "px-4", "w-full",
+            ]
+        );
+    }
+
+    #[test]
+    fn other_values_become_arbitrary() {
+        let theme = TailwindTheme::new(&TokenSet::default());
+        let classes = theme.classes(&styles(&[
+            ("background", "rgba(0, 0, 0, 0.5)"),
+            ("height", "13px"),
+            ("fontFamily", "\"Open Sans\""),
+            ("border", "1px solid #cccccc"),
+            ("borderRadius", "2px 4px 6px 8px"),
+            ("opacity", "0.33"),
+            ("transform", "rotate(45deg)"),
+        ]));
+        assert_eq!(
+            classes,
+            [
+                "bg-[rgba(0,0,0,0.5)]", "border", "border-solid", "border-[#cccccc]", "rounded-tl-sm",
+                "rounded-tr", "rounded-br-md", "rounded-bl-lg", "font-['Open_Sans']", "h-[13px]",
+                "opacity-[0.33]", "[transform:rotate(45deg)]",
+            ]
+        );
+    }
+
+    #[test]
+    fn token_values_use_the_preset_keys() {
+        let theme = TailwindTheme::new(&tokens());
+        let classes = theme.classes(&styles(&[("color", "var(--color-brand-primary, #0000ff)")]));
+        assert_eq!(classes, ["text-brand-primary"]);
+
+        let preset = theme.preset("@acme/button");
+        assert!(preset.contains("      colors: {\n        'brand-primary': 'var(--color-brand-primary)',\n      },\n"));
+        assert!(preset.contains("'./node_modules/@acme/button/dist/**/*.js'"));
+        assert!(!preset.contains("content:"));
+    }
+
+    #[test]
+    fn configured_scales_replace_the_defaults() {
+        let scales: TailwindScales = BTreeMap::from([
+            ("spacing".to_string(), BTreeMap::from([("sm".to_string(), "8px".to_string())])),
+            ("borderRadius".to_string(), BTreeMap::from([("DEFAULT".to_string(), "6px".to_string())])),
+        ]);
+        let theme = TailwindTheme::new(&TokenSet::default()).with_scales(&scales).unwrap();
+        let classes = theme.classes(&styles(&[("padding", "8px"), ("gap", "16px"), ("borderRadius", "6px")]));
+        // 16px is on the default scale but not the configured one
+        assert_eq!(classes, ["rounded", "gap-[16px]", "p-sm"]);
+
+        let unknown = BTreeMap::from([("spacings".to_string(), BTreeMap::new())]);
+        let error = TailwindTheme::new(&TokenSet::default()).with_scales(&unknown).err().unwrap();
+        assert!(error.starts_with("Unknown tailwind_theme section: spacings."));
+    }
+}
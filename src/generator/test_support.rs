@@ -0,0 +1,58 @@
+// Fixtures shared by the generator tests
+use std::collections::{BTreeMap, HashMap};
+use crate::generator::builder::{generate_package_files_from_node, GeneratedPackage};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::ElementNode;
+use crate::tokens::model::TokenSet;
+
+pub fn request(framework: &str) -> CreateComponentRequest {
+    CreateComponentRequest {
+        component_type: "button".to_string(),
+        name: "Button".to_string(),
+        config: HashMap::new(),
+        framework: framework.to_string(),
+        typescript: true,
+        package_name: "@acme/button".to_string(),
+        version: "1.0.0".to_string(),
+        tokens_file_key: None,
+        theme: None,
+        tag_prefix: None,
+        styling: None,
+        tailwind_theme: None,
+    }
+}
+
+pub fn element(
+    id: &str,
+    name: &str,
+    styles: &[(&str, &str)],
+    text: Option<&str>,
+    children: Vec<ElementNode>,
+) -> ElementNode {
+    ElementNode {
+        id: id.to_string(),
+        name: name.to_string(),
+        tag: if text.is_some() { "span" } else { "div" }.to_string(),
+        styles: styles
+            .iter()
+            .map(|(property, value)| (property.to_string(), value.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+        text: text.map(str::to_string),
+        children,
+    }
+}
+
+// A button frame holding a label
+pub fn button() -> ElementNode {
+    element(
+        "1:1",
+        "Button",
+        &[("background", "#0055ff"), ("padding", "8px 16px"), ("borderRadius", "4px")],
+        None,
+        vec![element("1:2", "Label", &[("color", "#ffffff"), ("fontSize", "14px")], Some("Click me"), vec![])],
+    )
+}
+
+pub fn generate(request: &CreateComponentRequest, root: &ElementNode) -> GeneratedPackage {
+    generate_package_files_from_node(request, root, &TokenSet::default()).unwrap()
+}
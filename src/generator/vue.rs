@@ -0,0 +1,245 @@
+// Vue 3 output: a single-file component with <script setup>, scoped styles
+// and a Vite library build
+use tera::{Context, Tera};
+use crate::generator::builder::{GeneratedPackage, GeneratedFile};
+use crate::generator::framework::{Framework, Styling};
+use crate::generator::markup::{class_names, render_stylesheet, render_template, Dialect};
+use crate::generator::models::CreateComponentRequest;
+use crate::generator::node_tree::ElementNode;
+use crate::tokens::css::render_files;
+use crate::tokens::model::TokenSet;
+
+pub fn add_templates(tera: &mut Tera) {
+    tera.add_raw_template("vue_component", r##"<script setup{% if typescript %} lang="ts"{% endif %}>
+{%- if typescript %}
+const props = withDefaults(defineProps<{
+  disabled?: boolean;
+}>(), {
+  disabled: false,
+});
+
+const emit = defineEmits<{
+  (e: 'click', event: MouseEvent): void;
+}>();
+
+function onClick(event: MouseEvent) {
+{%- else %}
+const props = defineProps({
+  disabled: { type: Boolean, default: false },
+});
+
+const emit = defineEmits(['click']);
+
+function onClick(event) {
+{%- endif %}
+  if (!props.disabled) {
+    emit('click', event);
+  }
+}
+</script>
+
+<template>
+{{ markup }}</template>
+
+<style scoped>
+{{ css }}</style>
+"##).expect("Failed to add vue_component template");
+
+    tera.add_raw_template("vue_package_json", r##"{
+  "name": "{{ package_name }}",
+  "version": "{{ version }}",
+  "description": "{{ name }} component",
+  "type": "module",
+  "main": "dist/index.cjs",
+  "module": "dist/index.js",
+  "types": "dist/index.d.ts",
+  "style": "styles/tokens.css",
+  "files": [
+    "dist",
+    "styles"
+  ],
+  "sideEffects": [
+    "*.css"
+  ],
+  "scripts": {
+    "build": "vite build",
+    "prepublishOnly": "npm run build"
+  },
+  "peerDependencies": {
+    "vue": "^3.3.0"
+  },
+  "devDependencies": {
+    "@vitejs/plugin-vue": "^5.0.0",
+    "typescript": "^5.0.0",
+    "vite": "^5.0.0",
+    "vite-plugin-css-injected-by-js": "^3.5.0",
+    "vite-plugin-dts": "^3.7.0",
+    "vue": "^3.3.0",
+    "vue-tsc": "^1.8.0"
+  },
+  "license": "MIT"
+}
+"##).expect("Failed to add vue_package_json template");
+}
+
+// Library mode: ES and CommonJS bundles, Vue left to the consumer. Vite
+// would extract the scoped styles to a dist/style.css that nothing imports,
+// so they are injected by the bundle instead.
+const VITE_CONFIG: &str = r#"import { defineConfig } from 'vite';
+import vue from '@vitejs/plugin-vue';
+import dts from 'vite-plugin-dts';
+import cssInjectedByJs from 'vite-plugin-css-injected-by-js';
+
+export default defineConfig({
+  plugins: [vue(), dts(), cssInjectedByJs()],
+  build: {
+    lib: {
+      entry: 'src/index.ts',
+      formats: ['es', 'cjs'],
+      fileName: (format) => (format === 'es' ? 'index.js' : 'index.cjs'),
+    },
+    rollupOptions: {
+      external: ['vue'],
+      output: {
+        exports: 'named',
+      },
+    },
+  },
+});
+"#;
+
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2020",
+    "module": "ESNext",
+    "moduleResolution": "bundler",
+    "lib": ["ES2020", "DOM"],
+    "jsx": "preserve",
+    "strict": true,
+    "declaration": true,
+    "isolatedModules": true,
+    "skipLibCheck": true
+  },
+  "include": ["src"],
+  "exclude": ["node_modules", "dist"]
+}
+"#;
+
+// Lets TypeScript import .vue files from index.ts
+const VUE_SHIM: &str = r#"declare module '*.vue' {
+  import type { DefineComponent } from 'vue';
+  const component: DefineComponent<object, object, unknown>;
+  export default component;
+}
+"#;
+
+fn dialect() -> Dialect {
+    Dialect {
+        root_attributes: vec![
+            ":aria-disabled=\"disabled || undefined\"".to_string(),
+            "@click=\"onClick\"".to_string(),
+        ],
+        children: "<slot />".to_string(),
+        interpolate: |literal| format!("{{{{ {} }}}}", literal),
+    }
+}
+
+pub fn generate(
+    tera: &Tera,
+    request: &CreateComponentRequest,
+    root: &ElementNode,
+    tokens: &TokenSet,
+) -> Result<GeneratedPackage, String> {
+    let classes = class_names(root);
+
+    let mut context = Context::new();
+    context.insert("name", &request.name);
+    context.insert("typescript", &request.typescript);
+    context.insert("package_name", &request.package_name);
+    context.insert("version", &request.version);
+    context.insert("markup", &render_template(root, &classes, &dialect(), 1));
+    context.insert("css", &render_stylesheet(root, &classes));
+
+    let component_code = tera.render("vue_component", &context)
+        .map_err(|e| format!("Component template error: {}", e))?;
+
+    let package_json = tera.render("vue_package_json", &context)
+        .map_err(|e| format!("Package.json template error: {}", e))?;
+
+    let framework = Framework::Vue;
+    let component_filename = framework.component_filename(&request.name, request.typescript);
+    let index_ts = format!(
+        "export {{ default as {} }} from './{}';\nexport * from './tokens';\nexport * from './themes';\n",
+        request.name, component_filename
+    );
+
+    Ok(GeneratedPackage {
+        framework,
+        styling: Styling::Inline,
+        component_filename,
+        component_code,
+        package_json,
+        index_ts,
+        tokens: render_files(tokens),
+        extra_files: vec![
+            GeneratedFile::new("vite.config.ts", VITE_CONFIG),
+            GeneratedFile::new("tsconfig.json", TSCONFIG),
+            GeneratedFile::new("src/env.d.ts", VUE_SHIM),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::test_support::{button, element, generate, request};
+
+    #[test]
+    fn layers_become_a_template_with_scoped_class_rules() {
+        let package = generate(&request("vue"), &button());
+        let code = &package.component_code;
+
+        assert!(code.starts_with("<script setup lang=\"ts\">\nconst props = withDefaults(defineProps<{\n"));
+        assert!(code.contains(
+            "<template>\n  <div\n    class=\"button\"\n    data-figma-id=\"1:1\"\n    \
+             :aria-disabled=\"disabled || undefined\"\n    @click=\"onClick\"\n  >\n    \
+             <span\n      class=\"label\"\n      data-figma-id=\"1:2\"\n    >\n      {{ 'Click me' }}\n    </span>\n    \
+             <slot />\n  </div>\n</template>\n"
+        ));
+        assert!(code.contains(
+            "<style scoped>\n.button {\n  background: #0055ff;\n  border-radius: 4px;\n  padding: 8px 16px;\n}\n\n\
+             .label {\n  color: #ffffff;\n  font-size: 14px;\n}\n</style>\n"
+        ));
+        assert_eq!(package.component_filename, "Button.vue");
+        assert!(package.index_ts.starts_with("export { default as Button } from './Button.vue';\n"));
+    }
+
+    #[test]
+    fn javascript_components_declare_runtime_props() {
+        let mut request = request("vue");
+        request.typescript = false;
+        let code = generate(&request, &button()).component_code;
+
+        assert!(code.starts_with("<script setup>\nconst props = defineProps({\n  disabled: { type: Boolean, default: false },\n});\n"));
+        assert!(code.contains("const emit = defineEmits(['click']);\n\nfunction onClick(event) {\n"));
+    }
+
+    #[test]
+    fn text_cannot_close_the_interpolation() {
+        let root = element("1:1", "Note", &[], Some("}} <b>{{ x }}</b>"), vec![]);
+        let code = generate(&request("vue"), &root).component_code;
+        assert!(code.contains("{{ '\\u007d\\u007d <b>\\u007b\\u007b x \\u007d\\u007d</b>' }}"));
+    }
+
+    #[test]
+    fn the_library_build_injects_the_styles() {
+        let package = generate(&request("vue"), &button());
+        let vite = package.extra_files.iter().find(|file| file.path == "vite.config.ts").unwrap();
+        assert!(vite.contents.contains("plugins: [vue(), dts(), cssInjectedByJs()],"));
+        assert!(vite.contents.contains("external: ['vue'],"));
+
+        let package_json: serde_json::Value = serde_json::from_str(&package.package_json).unwrap();
+        assert_eq!(package_json["devDependencies"]["vite-plugin-css-injected-by-js"], "^3.5.0");
+        assert_eq!(package_json["peerDependencies"]["vue"], "^3.3.0");
+        assert!(package.extra_files.iter().any(|file| file.path == "src/env.d.ts"));
+    }
+}
@@ -1,7 +1,10 @@
 use async_graphql::*;
 use mongodb::bson::doc;
 use crate::db::mongo::MongoDb;
-use crate::graphql::types::{FigmaFile, FigmaFileDocument, FigmaData, Document, Styles};
+use crate::figma::models::StoredNodes;
+use crate::figma::diff::{diff_stored, SnapshotDiff};
+use crate::figma::store::{self, normalize_node_id};
+use crate::graphql::types::{FigmaFile, FigmaData};
 
 pub struct QueryRoot;
 
@@ -13,29 +16,33 @@ impl QueryRoot {
         file_key: String,
     ) -> Result<Option<FigmaFile>> {
         let db = ctx.data::<MongoDb>()?;
-        
-        let filter = doc! { "file_key": &file_key };
-        
-        // Get the raw document
-        let document: Option<FigmaFileDocument> = db
-            .get_document_from_collection("figma_file", filter.clone())
+
+        // Get the current snapshot
+        let document = store::current_file(db, &file_key)
             .await
             .map_err(|e| Error::new(format!("Database error: {}", e)))?;
-        
+
         // Convert to our GraphQL type
         match document {
             Some(doc) => {
+                let data = doc
+                    .data
+                    .as_ref()
+                    .map(serde_json::to_value)
+                    .transpose()
+                    .map_err(|e| Error::new(format!("Serialization error: {}", e)))?;
                 Ok(Some(FigmaFile {
                     file_key: doc.file_key,
                     message: doc.message,
                     status: doc.status,
-                    data: doc.data,
+                    file: doc.data.as_ref().map(FigmaData::from),
+                    data,
                 }))
             }
             None => Ok(None),
         }
     }
-    
+
     async fn figma_file_data(
         &self,
         ctx: &Context<'_>,
@@ -44,20 +51,61 @@ impl QueryRoot {
     ) -> Result<Option<serde_json::Value>> {
         println!("File key: {:?}", file_key);
         println!("Node ID: {:?}", node_id);
-        
+
         let db = ctx.data::<MongoDb>()?;
-        
+
         // Option 1: Filter by both file_key and node_id
-        let filter = doc! { 
+        let filter = doc! {
             "file_key": &file_key,
-            "node_id": &node_id 
+            "node_id": normalize_node_id(&node_id),
+            "current": true
         };
-        
-        let document: Option<FigmaFileDocument> = db
+
+        let document: Option<StoredNodes> = db
             .get_document_from_collection("figma_nodes", filter)
             .await
             .map_err(|e| Error::new(format!("Database error: {}", e)))?;
-        
-        Ok(document.and_then(|doc| doc.data))
+
+        document
+            .and_then(|doc| doc.data)
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| Error::new(format!("Serialization error: {}", e)))
+    }
+
+    // Current stored variables of a file: "local" (default) or "published"
+    async fn figma_variables(
+        &self,
+        ctx: &Context<'_>,
+        file_key: String,
+        scope: Option<String>,
+    ) -> Result<Option<serde_json::Value>> {
+        let db = ctx.data::<MongoDb>()?;
+        let scope = scope.unwrap_or_else(|| "local".to_string());
+
+        let document = store::current_variables(db, &file_key, &scope)
+            .await
+            .map_err(|e| Error::new(format!("Database error: {}", e)))?;
+
+        document
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| Error::new(format!("Serialization error: {}", e)))
+    }
+
+    // Structural diff between two stored snapshots of a file or node
+    async fn figma_snapshot_diff(
+        &self,
+        ctx: &Context<'_>,
+        file_key: String,
+        from_version: String,
+        to_version: Option<String>,
+        node_id: Option<String>,
+    ) -> Result<SnapshotDiff> {
+        let db = ctx.data::<MongoDb>()?;
+
+        diff_stored(db, &file_key, &from_version, to_version.as_deref(), node_id.as_deref())
+            .await
+            .map_err(|e| Error::new(e.to_string()))
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
 use async_graphql::*;
 use serde::{Deserialize, Serialize};
+use crate::figma::models;
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct FigmaFile {
@@ -7,15 +8,17 @@ pub struct FigmaFile {
     pub message: String,
     pub status: String,
     pub data: Option<serde_json::Value>,
+    // Typed view of `data`
+    pub file: Option<FigmaData>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
 pub struct FigmaData {
     pub document: Option<Document>,
-    pub components: Option<serde_json::Value>,
+    pub components: Vec<Component>,
     #[graphql(name = "componentSets")]
-    pub component_sets: Option<serde_json::Value>,
-    pub styles: Option<Styles>,
+    pub component_sets: Vec<Component>,
+    pub styles: Vec<Style>,
     pub name: Option<String>,
     pub version: Option<String>,
     pub role: Option<String>,
@@ -31,22 +34,84 @@ pub struct Document {
     pub name: String,
     #[graphql(name = "type")]
     pub node_type: String,
+    pub visible: bool,
     pub children: Option<Vec<Document>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
-pub struct Styles {
-    pub fills: Option<Vec<serde_json::Value>>,
-    pub strokes: Option<Vec<serde_json::Value>>,
-    pub effects: Option<Vec<serde_json::Value>>,
-    pub grids: Option<Vec<serde_json::Value>>,
+pub struct Component {
+    pub id: String,
+    pub key: String,
+    pub name: String,
+    pub description: String,
 }
 
-// For more granular control, you can create a custom type that maps the MongoDB document
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FigmaFileDocument {
-    pub file_key: String,
-    pub message: String,
-    pub status: String,
-    pub data: Option<serde_json::Value>,
-}
\ No newline at end of file
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Style {
+    pub id: String,
+    pub key: String,
+    pub name: String,
+    #[graphql(name = "styleType")]
+    pub style_type: String,
+    pub description: String,
+}
+
+impl From<&models::FigmaFile> for FigmaData {
+    fn from(file: &models::FigmaFile) -> Self {
+        FigmaData {
+            document: Some(Document::from(&file.document)),
+            components: file
+                .components
+                .iter()
+                .map(|(id, c)| Component {
+                    id: id.clone(),
+                    key: c.key.clone(),
+                    name: c.name.clone(),
+                    description: c.description.clone(),
+                })
+                .collect(),
+            component_sets: file
+                .component_sets
+                .iter()
+                .map(|(id, c)| Component {
+                    id: id.clone(),
+                    key: c.key.clone(),
+                    name: c.name.clone(),
+                    description: c.description.clone(),
+                })
+                .collect(),
+            styles: file
+                .styles
+                .iter()
+                .map(|(id, s)| Style {
+                    id: id.clone(),
+                    key: s.key.clone(),
+                    name: s.name.clone(),
+                    style_type: s.style_type.clone(),
+                    description: s.description.clone(),
+                })
+                .collect(),
+            name: Some(file.name.clone()),
+            version: file.version.clone(),
+            role: file.role.clone(),
+            last_modified: file.last_modified.clone(),
+            thumbnail_url: file.thumbnail_url.clone(),
+        }
+    }
+}
+
+impl From<&models::Node> for Document {
+    fn from(node: &models::Node) -> Self {
+        Document {
+            id: node.id.clone(),
+            name: node.name.clone(),
+            node_type: node.node_type.as_str().to_string(),
+            visible: node.visible,
+            children: if node.children.is_empty() {
+                None
+            } else {
+                Some(node.children.iter().map(Document::from).collect())
+            },
+        }
+    }
+}
@@ -3,14 +3,16 @@ use std::io;
 use actix_web::middleware::Logger;
 use actix_cors::Cors;
 
+// Token middleware is not wired into any scope yet
+#[allow(dead_code, unused_imports)]
 mod auth;
 mod db;
 mod figma;
 mod mongo;
 mod graphql;
 mod generator;
+mod tokens;
 
-use auth::middleware::FigmaTokenMiddleware;
 use graphql::handler::{graphql_handler, graphql_playground};
 
 #[actix_web::main]
@@ -27,6 +29,35 @@ async fn main() -> io::Result<()> {
         }
     };
     
+    // Snapshot lookups by version rely on these being unique
+    if let Err(e) = figma::store::create_indexes(&mongodb).await {
+        eprintln!("Failed to create snapshot indexes: {}", e);
+        std::process::exit(1);
+    }
+    
+    // Shared Figma API client (connection pool is reused across workers)
+    let figma_client = match figma::client::FigmaClient::from_env() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to create Figma client: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    // Webhook settings are read once rather than on every delivery
+    let webhook_config = figma::webhook::WebhookConfig::from_env();
+    
+    // Package builds run in the background on a bounded worker pool
+    let build_jobs = generator::jobs::JobQueue::from_env(mongodb.clone());
+    if let Err(e) = build_jobs.create_indexes().await {
+        eprintln!("Failed to create build job indexes: {}", e);
+    }
+    match build_jobs.mark_interrupted().await {
+        Ok(0) => {}
+        Ok(count) => println!("Marked {} unfinished build jobs as interrupted", count),
+        Err(e) => eprintln!("Failed to mark unfinished build jobs as interrupted: {}", e),
+    }
+    
     // Build GraphQL schema
     let schema = graphql::build_schema();
     
@@ -46,6 +77,9 @@ async fn main() -> io::Result<()> {
             .wrap(cors)  // Add CORS middleware
             .app_data(web::Data::new(mongodb.clone()))
             .app_data(web::Data::new(schema.clone()))
+            .app_data(web::Data::new(figma_client.clone()))
+            .app_data(web::Data::new(build_jobs.clone()))
+            .app_data(web::Data::new(webhook_config.clone()))
             .service(
                 web::scope("/api")
                     .service(
@@ -61,6 +95,7 @@ async fn main() -> io::Result<()> {
                     .service(web::scope("/figma").configure(figma::routes::config))
                     .service(web::scope("/mongo").configure(mongo::routes::config))
                     .service(web::scope("/generator").configure(generator::configure_routes))
+                    .service(web::scope("/tokens").configure(tokens::routes::config))
 
             )
     })
@@ -0,0 +1,496 @@
+// CSS custom properties, SCSS variables and a typed TypeScript module
+// rendered from a token set. Property names are the token path joined with
+// dashes, so color.primary becomes --color-primary.
+use std::collections::HashMap;
+use crate::figma::models::Color;
+use crate::generator::node_tree::{css_color, format_number, js_string, px};
+use crate::tokens::theme::to_themes_ts;
+use crate::tokens::model::{Token, TokenSet, TokenSource, TokenValue, Typography};
+
+pub const HEADER: &str = "Generated design tokens. Do not edit by hand.";
+
+// The token files shipped in a generated package
+pub struct TokenFiles {
+    pub css: String,
+    pub scss: String,
+    pub typescript: String,
+    pub themes: String,
+}
+
+pub fn render_files(set: &TokenSet) -> TokenFiles {
+    TokenFiles {
+        css: to_css(set),
+        scss: to_scss(set),
+        typescript: to_typescript(set),
+        themes: to_themes_ts(set),
+    }
+}
+
+pub fn css_var(path: &[String]) -> String {
+    format!("--{}", path.join("-"))
+}
+
+// Default values on :root, and every theme's values for the tokens that
+// vary by mode under [data-theme="<theme>"], so themes can also be nested.
+// Aliases are repeated there because var() resolves where it is declared.
+pub fn to_css(set: &TokenSet) -> String {
+    let mut out = format!("/* {} */\n:root {{\n", HEADER);
+    for token in &set.tokens {
+        for (name, value) in properties(set, &token.path, &token.value) {
+            out.push_str(&format!("  {}: {};\n", name, value));
+        }
+    }
+    out.push_str("}\n");
+
+    for theme in set.themes() {
+        out.push_str(&format!("\n[data-theme=\"{}\"] {{\n", theme));
+        let themed = set
+            .tokens
+            .iter()
+            .filter(|token| !token.modes.is_empty() || matches!(token.value, TokenValue::Alias(_)));
+        for token in themed {
+            let value = set.value_in(token, Some(&theme));
+            for (name, value) in properties(set, &token.path, value) {
+                out.push_str(&format!("  {}: {};\n", name, value));
+            }
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+// SCSS is compiled ahead of time, so variables hold the resolved values of
+// the default mode
+pub fn to_scss(set: &TokenSet) -> String {
+    let mut out = format!("// {}\n", HEADER);
+    for token in &set.tokens {
+        let Some(value) = set.resolve(&token.value) else {
+            continue;
+        };
+        for (name, value) in properties(set, &token.path, value) {
+            out.push_str(&format!("${}: {};\n", name.trim_start_matches("--"), value));
+        }
+    }
+    out
+}
+
+// `tokens` holds the default values, `cssVars` the matching custom property
+// references for styling that should follow the active theme
+pub fn to_typescript(set: &TokenSet) -> String {
+    let mut values = TsGroup::default();
+    let mut vars = TsGroup::default();
+    for token in &set.tokens {
+        let Some(value) = set.resolve(&token.value) else {
+            continue;
+        };
+        values.insert(&token.path, ts_value(value));
+        vars.insert(&token.path, ts_var(&token.path, value));
+    }
+
+    let mut out = format!("// {}\n\n", HEADER);
+    out.push_str("// Values in the default theme\n");
+    out.push_str(&format!("export const tokens = {} as const;\n\n", values.render(0)));
+    out.push_str("export type Tokens = typeof tokens;\n\n");
+    out.push_str("// CSS custom property references, e.g. cssVars.color.primary\n");
+    out.push_str(&format!("export const cssVars = {} as const;\n", vars.render(0)));
+    out
+}
+
+// Custom properties for one token value. Typography is split into one
+// property per field; aliases point at the target's properties.
+fn properties(set: &TokenSet, path: &[String], value: &TokenValue) -> Vec<(String, String)> {
+    let name = css_var(path);
+    match value {
+        TokenValue::Alias(target) => match set.find(target).and_then(|t| set.resolve(&t.value)) {
+            Some(TokenValue::Typography(typography)) => typography_fields(typography)
+                .into_iter()
+                .map(|(field, _)| {
+                    (format!("{}-{}", name, field), format!("var({}-{})", css_var(target), field))
+                })
+                .collect(),
+            _ => vec![(name, format!("var({})", css_var(target)))],
+        },
+        TokenValue::Typography(typography) => typography_fields(typography)
+            .into_iter()
+            .map(|(field, value)| (format!("{}-{}", name, field), value))
+            .collect(),
+        literal => vec![(name, css_value(literal))],
+    }
+}
+
+// CSS text of a literal value, formatted like the generated components
+// format theirs so the two can be matched
+pub fn css_value(value: &TokenValue) -> String {
+    match value {
+        TokenValue::Color(color) => css_color(color, 1.0),
+        TokenValue::Dimension(value) => px(*value),
+        TokenValue::Number(value) => format_number(*value),
+        TokenValue::FontFamily(family) => font_family(family),
+        TokenValue::Shadow(shadows) => shadows
+            .iter()
+            .map(|shadow| {
+                format!(
+                    "{}{} {} {} {} {}",
+                    if shadow.inset { "inset " } else { "" },
+                    px(shadow.offset_x),
+                    px(shadow.offset_y),
+                    px(shadow.blur),
+                    px(shadow.spread),
+                    css_color(&shadow.color, 1.0)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        TokenValue::Alias(path) => format!("var({})", css_var(path)),
+        TokenValue::Typography(_) => String::new(),
+    }
+}
+
+// Field suffixes double as lookup categories, see TokenLookup
+fn typography_fields(typography: &Typography) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
+    if let Some(family) = &typography.font_family {
+        fields.push(("font-family", font_family(family)));
+    }
+    if let Some(size) = typography.font_size {
+        fields.push(("font-size", px(size)));
+    }
+    if let Some(weight) = typography.font_weight {
+        fields.push(("font-weight", format_number(weight)));
+    }
+    if let Some(line_height) = typography.line_height {
+        fields.push(("line-height", px(line_height)));
+    }
+    if let Some(spacing) = typography.letter_spacing {
+        fields.push(("letter-spacing", px(spacing)));
+    }
+    fields
+}
+
+fn font_family(family: &str) -> String {
+    if family.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        family.to_string()
+    } else {
+        format!("\"{}\"", family.replace('"', ""))
+    }
+}
+
+pub fn ts_value(value: &TokenValue) -> TsNode {
+    match value {
+        TokenValue::Number(value) => TsNode::Leaf(format_number(*value)),
+        TokenValue::Typography(typography) => TsNode::Leaf(ts_object(
+            typography_fields(typography)
+                .into_iter()
+                .map(|(field, value)| (camel_case(field), js_string(&value))),
+        )),
+        literal => TsNode::Leaf(js_string(&css_value(literal))),
+    }
+}
+
+fn ts_var(path: &[String], value: &TokenValue) -> TsNode {
+    let name = css_var(path);
+    match value {
+        TokenValue::Typography(typography) => TsNode::Leaf(ts_object(
+            typography_fields(typography).into_iter().map(|(field, _)| {
+                (camel_case(field), js_string(&format!("var({}-{})", name, field)))
+            }),
+        )),
+        _ => TsNode::Leaf(js_string(&format!("var({})", name))),
+    }
+}
+
+fn ts_object(fields: impl Iterator<Item = (String, String)>) -> String {
+    let fields: Vec<String> = fields.map(|(key, value)| format!("{}: {}", key, value)).collect();
+    format!("{{ {} }}", fields.join(", "))
+}
+
+fn camel_case(field: &str) -> String {
+    let mut out = String::new();
+    let mut upper = false;
+    for c in field.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+pub enum TsNode {
+    Leaf(String),
+    Group(TsGroup),
+}
+
+// Object literal that keeps the token order
+#[derive(Default)]
+pub struct TsGroup {
+    entries: Vec<(String, TsNode)>,
+}
+
+impl TsGroup {
+    pub fn insert(&mut self, path: &[String], leaf: TsNode) {
+        let Some((name, rest)) = path.split_first() else {
+            return;
+        };
+        if rest.is_empty() {
+            self.entries.push((name.clone(), leaf));
+            return;
+        }
+        let position = self.entries.iter().position(|(key, _)| key == name);
+        let index = position.unwrap_or_else(|| {
+            self.entries.push((name.clone(), TsNode::Group(TsGroup::default())));
+            self.entries.len() - 1
+        });
+        if let TsNode::Group(group) = &mut self.entries[index].1 {
+            group.insert(rest, leaf);
+        }
+    }
+
+    pub fn render(&self, indent: usize) -> String {
+        let pad = "  ".repeat(indent + 1);
+        let mut out = String::from("{\n");
+        for (key, node) in &self.entries {
+            let value = match node {
+                TsNode::Leaf(value) => value.clone(),
+                TsNode::Group(group) => group.render(indent + 1),
+            };
+            out.push_str(&format!("{}{}: {},\n", pad, ts_key(key), value));
+        }
+        out.push_str(&format!("{}}}", "  ".repeat(indent)));
+        out
+    }
+}
+
+fn ts_key(key: &str) -> String {
+    let identifier = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if identifier {
+        key.to_string()
+    } else {
+        js_string(key)
+    }
+}
+
+// Group of tokens a CSS property draws from. Token paths start with the
+// same names, e.g. color.primary or spacing.16.
+pub fn property_category(property: &str) -> Option<&'static str> {
+    match property {
+        "background" | "backgroundColor" | "color" | "borderColor" | "outlineColor" | "fill"
+        | "stroke" => Some("color"),
+        "borderRadius" => Some("radius"),
+        "gap" | "rowGap" | "columnGap" | "padding" | "paddingTop" | "paddingRight"
+        | "paddingBottom" | "paddingLeft" | "margin" | "marginTop" | "marginRight"
+        | "marginBottom" | "marginLeft" => Some("spacing"),
+        "fontSize" => Some("font-size"),
+        "lineHeight" => Some("line-height"),
+        "letterSpacing" => Some("letter-spacing"),
+        "fontFamily" => Some("font-family"),
+        "fontWeight" => Some("font-weight"),
+        "boxShadow" => Some("shadow"),
+        "opacity" => Some("opacity"),
+        "borderWidth" => Some("border-width"),
+        _ => None,
+    }
+}
+
+// #rgb, #rrggbb or #rrggbbaa
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    let channel = |i: usize, width: usize| -> Option<f64> {
+        let part = hex.get(i * width..(i + 1) * width)?;
+        let byte = u8::from_str_radix(part, 16).ok()?;
+        Some(if width == 1 { byte * 17 } else { byte } as f64 / 255.0)
+    };
+    match hex.len() {
+        3 => Some(Color { r: channel(0, 1)?, g: channel(1, 1)?, b: channel(2, 1)?, a: 1.0 }),
+        6 => Some(Color { r: channel(0, 2)?, g: channel(1, 2)?, b: channel(2, 2)?, a: 1.0 }),
+        8 => Some(Color { r: channel(0, 2)?, g: channel(1, 2)?, b: channel(2, 2)?, a: channel(3, 2)? }),
+        _ => None,
+    }
+}
+
+// Finds the token behind a literal style value so components can use the
+// custom property instead of the literal
+pub struct TokenLookup {
+    by_value: HashMap<(String, String), String>,
+}
+
+impl TokenLookup {
+    pub fn new(set: &TokenSet) -> Self {
+        // Semantic aliases win over the primitives they point to, and
+        // anything named wins over a repeated raw value
+        let rank = |token: &Token| match (token.source, &token.value) {
+            (TokenSource::Variable, TokenValue::Alias(_)) => 0,
+            (TokenSource::Style, _) => 1,
+            (TokenSource::Variable, _) | (TokenSource::Config, _) => 2,
+            (TokenSource::Raw, _) => 3,
+        };
+        let mut tokens: Vec<&Token> = set.tokens.iter().collect();
+        tokens.sort_by_key(|token| rank(token));
+
+        let mut by_value = HashMap::new();
+        for token in tokens {
+            let Some(value) = set.resolve(&token.value) else {
+                continue;
+            };
+            let name = css_var(&token.path);
+            match value {
+                TokenValue::Typography(typography) => {
+                    for (field, value) in typography_fields(typography) {
+                        by_value
+                            .entry((field.to_string(), normalize(field, &value)))
+                            .or_insert_with(|| format!("{}-{}", name, field));
+                    }
+                }
+                literal => {
+                    let category = token.path[0].clone();
+                    let key = normalize(&category, &css_value(literal));
+                    by_value.entry((category, key)).or_insert(name);
+                }
+            }
+        }
+        TokenLookup { by_value }
+    }
+
+    // `var(--token, literal)` for a style value that matches a token, keeping
+    // the literal as fallback for pages that do not load tokens.css
+    pub fn style_value(&self, property: &str, value: &str) -> Option<String> {
+        if property == "border" {
+            let (width, color) = value.split_once(" solid ")?;
+            let reference = self.reference("color", color)?;
+            return Some(format!("{} solid {}", width, reference));
+        }
+        self.reference(property_category(property)?, value)
+    }
+
+    fn reference(&self, category: &str, value: &str) -> Option<String> {
+        let name = self
+            .by_value
+            .get(&(category.to_string(), normalize(category, value)))?;
+        Some(format!("var({}, {})", name, value))
+    }
+}
+
+fn normalize(category: &str, value: &str) -> String {
+    let value = value.trim();
+    if category == "color"
+        && let Some(color) = parse_hex_color(value)
+    {
+        return css_color(&color, 1.0);
+    }
+    value.replace(['"', '\''], "").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::model::{Shadow, TokenType};
+    use crate::tokens::test_support::{path, token, token_set};
+
+    #[test]
+    fn root_holds_the_defaults_and_themes_override_the_modes() {
+        let css = to_css(&token_set());
+        let root = "/* Generated design tokens. Do not edit by hand. */\n:root {\n  \
+            --color-blue: #0055ff;\n  \
+            --color-surface: #ffffff;\n  \
+            --color-primary: var(--color-blue);\n  \
+            --spacing-sm: 8px;\n  \
+            --font-family-body: \"Inter Tight\";\n  \
+            --typography-heading-font-family: Inter;\n  \
+            --typography-heading-font-size: 24px;\n  \
+            --typography-heading-font-weight: 700;\n  \
+            --typography-heading-line-height: 32px;\n  \
+            --typography-title-font-family: var(--typography-heading-font-family);\n  \
+            --typography-title-font-size: var(--typography-heading-font-size);\n  \
+            --typography-title-font-weight: var(--typography-heading-font-weight);\n  \
+            --typography-title-line-height: var(--typography-heading-line-height);\n}\n";
+        assert!(css.starts_with(root));
+        // Aliases are repeated in every theme so they pick up its values
+        assert!(css.contains(
+            "\n[data-theme=\"dark-mode\"] {\n  \
+             --color-blue: #002288;\n  \
+             --color-surface: #111111;\n  \
+             --color-primary: var(--color-blue);\n  \
+             --typography-title-font-family: var(--typography-heading-font-family);\n"
+        ));
+        assert!(css.contains("\n[data-theme=\"light\"] {\n  --color-blue: #0055ff;\n"));
+        assert!(!css.contains("[data-theme=\"light\"] {\n  --spacing-sm"));
+    }
+
+    #[test]
+    fn sets_without_modes_have_no_theme_blocks() {
+        let set = TokenSet { tokens: vec![token(&["spacing", "sm"], TokenType::Dimension, TokenValue::Dimension(4.5))] };
+        assert_eq!(to_css(&set), format!("/* {} */\n:root {{\n  --spacing-sm: 4.5px;\n}}\n", HEADER));
+    }
+
+    #[test]
+    fn scss_variables_hold_resolved_values() {
+        let scss = to_scss(&token_set());
+        assert!(scss.starts_with("// Generated design tokens. Do not edit by hand.\n"));
+        assert!(scss.contains("$color-primary: #0055ff;\n"));
+        assert!(scss.contains("$typography-title-font-size: 24px;\n"));
+        assert!(scss.contains("$font-family-body: \"Inter Tight\";\n"));
+
+        let mut set = token_set();
+        set.tokens.push(token(&["color", "broken"], TokenType::Color, TokenValue::Alias(path(&["color", "gone"]))));
+        assert!(!to_scss(&set).contains("broken"));
+    }
+
+    #[test]
+    fn typescript_exports_values_and_custom_properties() {
+        let ts = to_typescript(&token_set());
+        assert!(ts.contains(
+            "export const tokens = {\n  \
+             color: {\n    blue: '#0055ff',\n    surface: '#ffffff',\n    primary: '#0055ff',\n  },\n  \
+             spacing: {\n    sm: '8px',\n  },\n  \
+             'font-family': {\n    body: '\"Inter Tight\"',\n  },\n"
+        ));
+        assert!(ts.contains(
+            "    title: { fontFamily: 'Inter', fontSize: '24px', fontWeight: '700', lineHeight: '32px' },\n"
+        ));
+        assert!(ts.contains("export const cssVars = {\n  color: {\n    blue: 'var(--color-blue)',\n"));
+        assert!(ts.contains("    heading: { fontFamily: 'var(--typography-heading-font-family)', "));
+        assert!(ts.contains("export type Tokens = typeof tokens;\n"));
+    }
+
+    #[test]
+    fn literal_values_are_formatted_like_component_styles() {
+        let shadow = Shadow {
+            color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.25 },
+            offset_x: 0.0,
+            offset_y: 2.0,
+            blur: 4.0,
+            spread: 0.0,
+            inset: false,
+        };
+        assert_eq!(css_value(&TokenValue::Shadow(vec![shadow])), "0px 2px 4px 0px rgba(0, 0, 0, 0.25)");
+        assert_eq!(css_value(&TokenValue::Number(1.5)), "1.5");
+        assert_eq!(css_value(&TokenValue::FontFamily("system-ui".to_string())), "system-ui");
+        assert_eq!(css_value(&TokenValue::Alias(path(&["color", "blue"]))), "var(--color-blue)");
+    }
+
+    #[test]
+    fn lookup_swaps_matching_literals_for_custom_properties() {
+        let lookup = TokenLookup::new(&token_set());
+        // The semantic alias wins over the primitive it points to
+        assert_eq!(
+            lookup.style_value("background", "#0055FF").as_deref(),
+            Some("var(--color-primary, #0055FF)")
+        );
+        assert_eq!(lookup.style_value("paddingTop", "8px").as_deref(), Some("var(--spacing-sm, 8px)"));
+        assert_eq!(
+            lookup.style_value("border", "1px solid #fff").as_deref(),
+            Some("1px solid var(--color-surface, #fff)")
+        );
+        assert_eq!(
+            lookup.style_value("fontSize", "24px").as_deref(),
+            Some("var(--typography-title-font-size, 24px)")
+        );
+        assert_eq!(lookup.style_value("width", "8px"), None);
+        assert_eq!(lookup.style_value("gap", "12px"), None);
+    }
+}
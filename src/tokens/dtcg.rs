@@ -0,0 +1,193 @@
+// Serialization to the W3C Design Tokens Community Group format:
+// nested groups, with `$type` / `$value` on every token and aliases written
+// as "{group.token}".
+use serde_json::{json, Map, Value};
+use crate::figma::models::Color;
+use crate::generator::node_tree::format_number;
+use crate::tokens::model::{Shadow, Token, TokenSet, TokenValue};
+
+pub fn to_dtcg(set: &TokenSet) -> Value {
+    let mut root = Map::new();
+    for token in &set.tokens {
+        insert(&mut root, &token.path, token_json(token));
+    }
+    Value::Object(root)
+}
+
+// Token paths never prefix one another (extraction moves such tokens to
+// "<path>.default"), so a group is never a token's own object
+fn insert(group: &mut Map<String, Value>, path: &[String], token: Value) {
+    let Some((name, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        group.insert(name.clone(), token);
+        return;
+    }
+    let child = group
+        .entry(name.clone())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(child) = child {
+        insert(child, rest, token);
+    }
+}
+
+fn token_json(token: &Token) -> Value {
+    let mut out = Map::new();
+    out.insert("$type".to_string(), json!(token.token_type.as_str()));
+    out.insert("$value".to_string(), render_value(&token.value));
+    if let Some(description) = token.description.as_ref().filter(|d| !d.is_empty()) {
+        out.insert("$description".to_string(), json!(description));
+    }
+
+    let mut figma = Map::new();
+    figma.insert("source".to_string(), json!(token.source.as_str()));
+    if !token.modes.is_empty() {
+        let modes: Map<String, Value> = token
+            .modes
+            .iter()
+            .map(|(mode, value)| (mode.clone(), render_value(value)))
+            .collect();
+        figma.insert("modes".to_string(), Value::Object(modes));
+    }
+    if token.occurrences > 0 {
+        figma.insert("occurrences".to_string(), json!(token.occurrences));
+    }
+    out.insert("$extensions".to_string(), json!({ "com.figma": figma }));
+
+    Value::Object(out)
+}
+
+pub fn render_value(value: &TokenValue) -> Value {
+    match value {
+        TokenValue::Alias(path) => json!(alias_reference(path)),
+        TokenValue::Color(color) => json!(hex_color(color)),
+        TokenValue::Dimension(value) => json!(format!("{}px", format_number(*value))),
+        TokenValue::Number(value) => number(*value),
+        TokenValue::FontFamily(family) => json!(family),
+        TokenValue::Typography(typography) => {
+            let mut out = Map::new();
+            if let Some(family) = &typography.font_family {
+                out.insert("fontFamily".to_string(), json!(family));
+            }
+            if let Some(size) = typography.font_size {
+                out.insert("fontSize".to_string(), json!(format!("{}px", format_number(size))));
+            }
+            if let Some(weight) = typography.font_weight {
+                out.insert("fontWeight".to_string(), number(weight));
+            }
+            if let Some(line_height) = typography.line_height {
+                out.insert("lineHeight".to_string(), json!(format!("{}px", format_number(line_height))));
+            }
+            if let Some(spacing) = typography.letter_spacing {
+                out.insert("letterSpacing".to_string(), json!(format!("{}px", format_number(spacing))));
+            }
+            Value::Object(out)
+        }
+        TokenValue::Shadow(shadows) => match shadows.as_slice() {
+            [single] => shadow_json(single),
+            many => Value::Array(many.iter().map(shadow_json).collect()),
+        },
+    }
+}
+
+fn shadow_json(shadow: &Shadow) -> Value {
+    let mut out = json!({
+        "color": hex_color(&shadow.color),
+        "offsetX": format!("{}px", format_number(shadow.offset_x)),
+        "offsetY": format!("{}px", format_number(shadow.offset_y)),
+        "blur": format!("{}px", format_number(shadow.blur)),
+        "spread": format!("{}px", format_number(shadow.spread)),
+    });
+    if shadow.inset {
+        out["inset"] = json!(true);
+    }
+    out
+}
+
+// Whole numbers are written without a fraction, e.g. font weights
+fn number(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+pub fn alias_reference(path: &[String]) -> String {
+    format!("{{{}}}", path.join("."))
+}
+
+// #rrggbb, or #rrggbbaa when the color is translucent
+pub fn hex_color(color: &Color) -> String {
+    let byte = |channel: f64| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let rgb = format!("#{:02x}{:02x}{:02x}", byte(color.r), byte(color.g), byte(color.b));
+    if color.a >= 1.0 {
+        rgb
+    } else {
+        format!("{}{:02x}", rgb, byte(color.a))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::model::TokenType;
+    use crate::tokens::test_support::token;
+
+    #[test]
+    fn tokens_nest_by_path_with_type_and_value() {
+        let set = TokenSet {
+            tokens: vec![
+                token(&["color", "brand", "primary"], TokenType::Color, TokenValue::Color(Color { r: 1.0, g: 0.0, b: 0.0, a: 1.0 })),
+                token(&["color", "link"], TokenType::Color, TokenValue::Alias(vec!["color".into(), "brand".into(), "primary".into()])),
+                token(&["spacing", "sm"], TokenType::Dimension, TokenValue::Dimension(4.5)),
+                token(&["font-weight", "bold"], TokenType::FontWeight, TokenValue::Number(700.0)),
+            ],
+        };
+        let out = to_dtcg(&set);
+
+        assert_eq!(out["color"]["brand"]["primary"]["$type"], "color");
+        assert_eq!(out["color"]["brand"]["primary"]["$value"], "#ff0000");
+        assert_eq!(out["color"]["link"]["$value"], "{color.brand.primary}");
+        assert_eq!(out["spacing"]["sm"]["$value"], "4.5px");
+        assert_eq!(out["font-weight"]["bold"]["$value"], json!(700));
+        assert_eq!(out["spacing"]["sm"]["$extensions"]["com.figma"]["source"], "variable");
+    }
+
+    #[test]
+    fn modes_and_shadows_are_rendered() {
+        let mut themed = token(&["color", "bg"], TokenType::Color, TokenValue::Color(Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 }));
+        themed.modes = vec![
+            ("Light".to_string(), TokenValue::Color(Color { r: 1.0, g: 1.0, b: 1.0, a: 1.0 })),
+            ("Dark".to_string(), TokenValue::Color(Color { r: 0.0, g: 0.0, b: 0.0, a: 0.5 })),
+        ];
+        let shadow = Shadow {
+            color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.25 },
+            offset_x: 0.0,
+            offset_y: 2.0,
+            blur: 4.0,
+            spread: 0.0,
+            inset: true,
+        };
+        let set = TokenSet {
+            tokens: vec![themed, token(&["shadow", "card"], TokenType::Shadow, TokenValue::Shadow(vec![shadow]))],
+        };
+        let out = to_dtcg(&set);
+
+        let modes = &out["color"]["bg"]["$extensions"]["com.figma"]["modes"];
+        assert_eq!(modes["Light"], "#ffffff");
+        assert_eq!(modes["Dark"], "#00000080");
+        assert_eq!(
+            out["shadow"]["card"]["$value"],
+            json!({
+                "color": "#00000040",
+                "offsetX": "0px",
+                "offsetY": "2px",
+                "blur": "4px",
+                "spread": "0px",
+                "inset": true,
+            })
+        );
+    }
+}
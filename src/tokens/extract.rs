@@ -0,0 +1,763 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use crate::db::mongo::MongoDb;
+use crate::figma::models::{
+    Color, Effect, EffectType, FigmaFile, LocalVariables, Node, NodeType, Paint, PaintType,
+    TypeStyle, Variable, VariableType, VariableValue,
+};
+use crate::figma::store;
+use crate::generator::node_tree::format_number;
+use crate::tokens::css::{parse_hex_color, property_category};
+use crate::tokens::dtcg::render_value;
+use crate::tokens::model::{
+    token_segment, Shadow, Token, TokenSet, TokenSource, TokenType, TokenValue, Typography,
+};
+
+// A raw value must appear this often before it becomes a token
+pub const DEFAULT_MIN_OCCURRENCES: usize = 3;
+
+// Tokens of the current stored snapshot of a file, or None when the file
+// has not been fetched yet. Variables are used when they were stored too.
+pub async fn tokens_for_file(
+    db: &MongoDb,
+    file_key: &str,
+    min_occurrences: usize,
+) -> Result<Option<TokenSet>, mongodb::error::Error> {
+    let Some(file) = store::current_file(db, file_key).await?.and_then(|stored| stored.data) else {
+        return Ok(None);
+    };
+    let variables = store::current_variables(db, file_key, "local")
+        .await?
+        .and_then(|stored| stored.local);
+    Ok(Some(extract_tokens(&file, variables.as_ref(), min_occurrences)))
+}
+
+// Variables come first so styles and raw values can alias them, then
+// styles, then literal values repeated at least `min_occurrences` times
+pub fn extract_tokens(
+    file: &FigmaFile,
+    variables: Option<&LocalVariables>,
+    min_occurrences: usize,
+) -> TokenSet {
+    let mut builder = Builder::default();
+    if let Some(variables) = variables {
+        builder.add_variables(variables);
+    }
+    builder.add_styles(file);
+    builder.add_raw_values(&file.document, min_occurrences.max(1));
+    builder.finish()
+}
+
+// Tokens for a component built from a config map instead of a Figma file:
+// backgroundColor of CustomButton becomes color.custom-button.background-color
+pub fn tokens_from_config(name: &str, config: &HashMap<String, String>) -> TokenSet {
+    let component = token_segment(name);
+    let sorted: BTreeMap<&String, &String> = config.iter().collect();
+
+    let mut tokens = Vec::new();
+    for (property, value) in sorted {
+        let Some(category) = property_category(property) else {
+            continue;
+        };
+        let parsed = match category {
+            "color" => parse_hex_color(value).map(|color| (TokenType::Color, TokenValue::Color(color))),
+            "font-family" => Some((
+                TokenType::FontFamily,
+                TokenValue::FontFamily(value.trim().trim_matches(['"', '\'']).to_string()),
+            )),
+            "font-weight" => value.trim().parse().ok().map(|v| (TokenType::FontWeight, TokenValue::Number(v))),
+            "opacity" => value.trim().parse().ok().map(|v| (TokenType::Number, TokenValue::Number(v))),
+            "shadow" => None,
+            _ => value
+                .trim()
+                .trim_end_matches("px")
+                .parse()
+                .ok()
+                .map(|v| (TokenType::Dimension, TokenValue::Dimension(v))),
+        };
+        let Some((token_type, value)) = parsed else {
+            continue;
+        };
+        tokens.push(Token {
+            path: vec![category.to_string(), component.clone(), token_segment(property)],
+            token_type,
+            value,
+            description: None,
+            modes: Vec::new(),
+            default_mode: None,
+            source: TokenSource::Config,
+            occurrences: 0,
+        });
+    }
+    TokenSet { tokens }
+}
+
+#[derive(Default)]
+struct Builder {
+    tokens: Vec<Token>,
+    taken: HashSet<Vec<String>>,
+    // Variable id -> token path, so aliases can point at it
+    variable_paths: HashMap<String, Vec<String>>,
+}
+
+impl Builder {
+    // Reserve a path, numbering it if another token already has it
+    fn claim(&mut self, path: Vec<String>) -> Vec<String> {
+        let mut candidate = path.clone();
+        let mut n = 2;
+        while self.taken.contains(&candidate) {
+            if let (Some(last), Some(original)) = (candidate.last_mut(), path.last()) {
+                *last = format!("{}-{}", original, n);
+            }
+            n += 1;
+        }
+        self.taken.insert(candidate.clone());
+        candidate
+    }
+
+    fn add_variables(&mut self, variables: &LocalVariables) {
+        // Collection order, then Figma's order inside each collection
+        let mut collections: Vec<_> = variables.variable_collections.values().collect();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut ordered: Vec<&Variable> = collections
+            .iter()
+            .flat_map(|collection| collection.variable_ids.iter())
+            .filter_map(|id| variables.variables.get(id))
+            .collect();
+        let listed: HashSet<&str> = ordered.iter().map(|v| v.id.as_str()).collect();
+        let mut unlisted: Vec<&Variable> = variables
+            .variables
+            .values()
+            .filter(|v| !listed.contains(v.id.as_str()))
+            .collect();
+        unlisted.sort_by(|a, b| a.name.cmp(&b.name));
+        ordered.extend(unlisted);
+
+        // Paths first, so aliases resolve regardless of order
+        let mut planned = Vec::new();
+        for variable in ordered {
+            let Some((token_type, root)) = classify(variable) else {
+                continue;
+            };
+            let path = self.claim(group_path(root, &variable.name));
+            self.variable_paths.insert(variable.id.clone(), path.clone());
+            planned.push((variable, token_type, path));
+        }
+
+        for (variable, token_type, path) in planned {
+            let collection = variables.variable_collections.get(&variable.variable_collection_id);
+            let default_mode = collection
+                .map(|c| c.default_mode_id.clone())
+                .or_else(|| variable.values_by_mode.keys().next().cloned())
+                .unwrap_or_default();
+            let Some(value) = self.variable_value(variables, variable, &default_mode, token_type) else {
+                continue;
+            };
+
+            let default_mode_name = collection
+                .filter(|c| c.modes.len() > 1)
+                .and_then(|c| c.modes.iter().find(|m| m.mode_id == default_mode))
+                .map(|m| m.name.clone());
+            let modes = match collection {
+                Some(collection) if collection.modes.len() > 1 => collection
+                    .modes
+                    .iter()
+                    .filter_map(|mode| {
+                        self.variable_value(variables, variable, &mode.mode_id, token_type)
+                            .map(|value| (mode.name.clone(), value))
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            self.tokens.push(Token {
+                path,
+                token_type,
+                value,
+                description: Some(variable.description.clone()),
+                modes,
+                default_mode: default_mode_name,
+                source: TokenSource::Variable,
+                occurrences: 0,
+            });
+        }
+    }
+
+    // Aliases to variables that became tokens stay aliases; others, e.g.
+    // to library variables, are resolved to their value
+    fn variable_value(
+        &self,
+        variables: &LocalVariables,
+        variable: &Variable,
+        mode_id: &str,
+        token_type: TokenType,
+    ) -> Option<TokenValue> {
+        match variable.values_by_mode.get(mode_id)? {
+            VariableValue::Alias(alias) => match self.variable_paths.get(&alias.id) {
+                Some(path) => Some(TokenValue::Alias(path.clone())),
+                None => variables
+                    .resolve(&variable.id, mode_id)
+                    .resolved
+                    .and_then(|value| literal(&value, token_type)),
+            },
+            value => literal(value, token_type),
+        }
+    }
+
+    fn add_styles(&mut self, file: &FigmaFile) {
+        // Style values live on the nodes that use them
+        let mut usage: HashMap<&str, (&Node, &str)> = HashMap::new();
+        collect_style_usage(&file.document, &mut usage);
+
+        let mut styles: Vec<_> = file.styles.iter().collect();
+        styles.sort_by(|a, b| (&a.1.style_type, &a.1.name).cmp(&(&b.1.style_type, &b.1.name)));
+
+        for (id, meta) in styles {
+            let Some((node, key)) = usage.get(id.as_str()) else {
+                continue;
+            };
+            let (token_type, root, value) = match meta.style_type.as_str() {
+                "FILL" => {
+                    let paints = if key.starts_with("stroke") { &node.strokes } else { &node.fills };
+                    let Some(paint) = paints.iter().find(|p| solid_color(p).is_some()) else {
+                        continue;
+                    };
+                    let value = match bound_variable_id(paint.bound_variables.as_ref(), "color")
+                        .and_then(|id| self.variable_paths.get(id))
+                    {
+                        Some(path) => TokenValue::Alias(path.clone()),
+                        None => match solid_color(paint) {
+                            Some(color) => TokenValue::Color(color),
+                            None => continue,
+                        },
+                    };
+                    (TokenType::Color, "color", value)
+                }
+                "TEXT" => match &node.style {
+                    Some(style) => (TokenType::Typography, "typography", TokenValue::Typography(typography(style))),
+                    None => continue,
+                },
+                "EFFECT" => {
+                    let shadows = shadows(&node.effects);
+                    if shadows.is_empty() {
+                        continue;
+                    }
+                    (TokenType::Shadow, "shadow", TokenValue::Shadow(shadows))
+                }
+                _ => continue,
+            };
+
+            let path = self.claim(group_path(root, &meta.name));
+            self.tokens.push(Token {
+                path,
+                token_type,
+                value,
+                description: Some(meta.description.clone()),
+                modes: Vec::new(),
+                default_mode: None,
+                source: TokenSource::Style,
+                occurrences: 0,
+            });
+        }
+    }
+
+    fn add_raw_values(&mut self, document: &Node, min_occurrences: usize) {
+        let mut tally = Tally::default();
+        tally_node(document, &mut tally);
+
+        // Values already covered by a variable or style are not repeated
+        let known: HashSet<(TokenType, String)> = self
+            .tokens
+            .iter()
+            .filter(|token| !matches!(token.value, TokenValue::Alias(_)))
+            .map(|token| (token.token_type, render_value(&token.value).to_string()))
+            .collect();
+
+        let mut entries: Vec<RawValue> = tally
+            .entries
+            .into_iter()
+            .filter(|entry| entry.count >= min_occurrences)
+            .filter(|entry| !known.contains(&(entry.token_type, render_value(&entry.value).to_string())))
+            .collect();
+        // Scales read best in ascending order, everything else by frequency
+        entries.sort_by(|a, b| {
+            a.root.cmp(b.root).then_with(|| match (&a.value, &b.value) {
+                (TokenValue::Dimension(x), TokenValue::Dimension(y)) => x.total_cmp(y),
+                _ => b.count.cmp(&a.count),
+            })
+        });
+
+        let mut shadow_number = 0;
+        for entry in entries {
+            let name = match &entry.value {
+                TokenValue::Color(color) => render_value(&TokenValue::Color(*color))
+                    .as_str()
+                    .unwrap_or_default()
+                    .trim_start_matches('#')
+                    .to_string(),
+                TokenValue::Dimension(value) => format_number(*value).replace('.', "-"),
+                TokenValue::Typography(typography) => token_segment(&format!(
+                    "{} {} {}",
+                    typography.font_family.as_deref().unwrap_or("font"),
+                    typography.font_size.map(format_number).unwrap_or_default(),
+                    typography.font_weight.map(format_number).unwrap_or_default(),
+                )),
+                _ => {
+                    shadow_number += 1;
+                    shadow_number.to_string()
+                }
+            };
+            let path = self.claim(vec![entry.root.to_string(), name]);
+            self.tokens.push(Token {
+                path,
+                token_type: entry.token_type,
+                value: entry.value,
+                description: None,
+                modes: Vec::new(),
+                default_mode: None,
+                source: TokenSource::Raw,
+                occurrences: entry.count,
+            });
+        }
+    }
+
+    // A token cannot also be a group, so a token whose path prefixes another
+    // moves to "<path>.default" ("default-2"... when that is taken) and
+    // aliases to it follow. In sorted order a path's extensions come right
+    // after it, so comparing neighbours finds every such token.
+    fn finish(mut self) -> TokenSet {
+        let paths: BTreeSet<Vec<String>> = self.tokens.iter().map(|t| t.path.clone()).collect();
+        let mut renamed: HashMap<Vec<String>, Vec<String>> = HashMap::new();
+        for (group, next) in paths.iter().zip(paths.iter().skip(1)) {
+            if next.starts_with(group) {
+                renamed.insert(group.clone(), free_child(&paths, group, "default"));
+            }
+        }
+        for token in &mut self.tokens {
+            if let Some(path) = renamed.get(&token.path) {
+                token.path = path.clone();
+            }
+        }
+
+        if !renamed.is_empty() {
+            for token in &mut self.tokens {
+                let values = std::iter::once(&mut token.value)
+                    .chain(token.modes.iter_mut().map(|(_, value)| value));
+                for value in values {
+                    if let TokenValue::Alias(path) = value
+                        && let Some(new_path) = renamed.get(path)
+                    {
+                        *path = new_path.clone();
+                    }
+                }
+            }
+        }
+
+        TokenSet { tokens: self.tokens }
+    }
+}
+
+// `group` + `name`, numbered until it is neither a token nor a group
+fn free_child(paths: &BTreeSet<Vec<String>>, group: &[String], name: &str) -> Vec<String> {
+    let mut n = 1;
+    loop {
+        let mut candidate = group.to_vec();
+        candidate.push(if n == 1 { name.to_string() } else { format!("{}-{}", name, n) });
+        let used = paths
+            .range(candidate.clone()..)
+            .next()
+            .is_some_and(|path| path.starts_with(&candidate));
+        if !used {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+// Token type and top-level group for a variable, from its scopes and name
+fn classify(variable: &Variable) -> Option<(TokenType, &'static str)> {
+    let has_scope = |scope: &str| variable.scopes.iter().any(|s| s == scope);
+    let name = variable.name.to_lowercase();
+
+    match variable.resolved_type {
+        VariableType::Color => Some((TokenType::Color, "color")),
+        VariableType::Float => {
+            if has_scope("CORNER_RADIUS") {
+                Some((TokenType::Dimension, "radius"))
+            } else if has_scope("GAP") || has_scope("WIDTH_HEIGHT") {
+                Some((TokenType::Dimension, "spacing"))
+            } else if has_scope("FONT_SIZE") {
+                Some((TokenType::Dimension, "font-size"))
+            } else if has_scope("LINE_HEIGHT") {
+                Some((TokenType::Dimension, "line-height"))
+            } else if has_scope("LETTER_SPACING") {
+                Some((TokenType::Dimension, "letter-spacing"))
+            } else if has_scope("FONT_WEIGHT") {
+                Some((TokenType::FontWeight, "font-weight"))
+            } else if has_scope("OPACITY") {
+                Some((TokenType::Number, "opacity"))
+            } else if has_scope("STROKE_FLOAT") {
+                Some((TokenType::Dimension, "border-width"))
+            } else if ["radius", "radii", "corner"].iter().any(|hint| name.contains(hint)) {
+                Some((TokenType::Dimension, "radius"))
+            } else if ["spacing", "space", "gap", "padding", "margin"].iter().any(|hint| name.contains(hint)) {
+                Some((TokenType::Dimension, "spacing"))
+            } else {
+                Some((TokenType::Number, "number"))
+            }
+        }
+        VariableType::String if has_scope("FONT_FAMILY") || name.contains("font") || name.contains("family") => {
+            Some((TokenType::FontFamily, "font-family"))
+        }
+        // Booleans and free-form strings have no token type
+        _ => None,
+    }
+}
+
+fn literal(value: &VariableValue, token_type: TokenType) -> Option<TokenValue> {
+    match (value, token_type) {
+        (VariableValue::Color(color), TokenType::Color) => Some(TokenValue::Color(*color)),
+        (VariableValue::Float(value), TokenType::Dimension) => Some(TokenValue::Dimension(*value)),
+        (VariableValue::Float(value), TokenType::Number | TokenType::FontWeight) => {
+            Some(TokenValue::Number(*value))
+        }
+        (VariableValue::String(family), TokenType::FontFamily) => {
+            Some(TokenValue::FontFamily(family.clone()))
+        }
+        _ => None,
+    }
+}
+
+// "Colors/Brand/Primary" in the color group becomes color.brand.primary
+fn group_path(root: &str, name: &str) -> Vec<String> {
+    let mut segments: Vec<String> = name
+        .split('/')
+        .filter(|part| !part.trim().is_empty())
+        .map(token_segment)
+        .collect();
+    if let Some(first) = segments.first()
+        && (first == root || *first == format!("{}s", root) || (root == "radius" && first == "radii"))
+    {
+        segments.remove(0);
+    }
+    if segments.is_empty() {
+        segments.push("default".to_string());
+    }
+
+    let mut path = vec![root.to_string()];
+    path.extend(segments);
+    path
+}
+
+fn collect_style_usage<'a>(node: &'a Node, usage: &mut HashMap<&'a str, (&'a Node, &'a str)>) {
+    for (key, style_id) in &node.styles {
+        usage.entry(style_id.as_str()).or_insert((node, key.as_str()));
+    }
+    for child in &node.children {
+        collect_style_usage(child, usage);
+    }
+}
+
+// Bound variables are either {"id": ..} or a list of them, e.g. for fills
+fn bound_variable_id<'a>(bound: Option<&'a Value>, field: &str) -> Option<&'a str> {
+    let binding = bound?.get(field)?;
+    let binding = match binding {
+        Value::Array(items) => items.first()?,
+        other => other,
+    };
+    binding.get("id")?.as_str()
+}
+
+fn solid_color(paint: &Paint) -> Option<Color> {
+    if !paint.visible || paint.paint_type != PaintType::Solid {
+        return None;
+    }
+    paint.color.map(|color| Color {
+        a: color.a * paint.opacity,
+        ..color
+    })
+}
+
+fn typography(style: &TypeStyle) -> Typography {
+    Typography {
+        font_family: style.font_family.clone(),
+        font_size: style.font_size,
+        font_weight: style.font_weight,
+        line_height: style.line_height_px,
+        letter_spacing: style.letter_spacing.filter(|spacing| *spacing != 0.0),
+    }
+}
+
+fn shadows(effects: &[Effect]) -> Vec<Shadow> {
+    effects
+        .iter()
+        .filter(|effect| effect.visible)
+        .filter_map(|effect| {
+            let inset = match effect.effect_type {
+                EffectType::DropShadow => false,
+                EffectType::InnerShadow => true,
+                _ => return None,
+            };
+            let (offset_x, offset_y) = effect.offset.map(|o| (o.x, o.y)).unwrap_or((0.0, 0.0));
+            Some(Shadow {
+                color: effect.color.unwrap_or(Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }),
+                offset_x,
+                offset_y,
+                blur: effect.radius,
+                spread: effect.spread.unwrap_or(0.0),
+                inset,
+            })
+        })
+        .collect()
+}
+
+struct RawValue {
+    root: &'static str,
+    token_type: TokenType,
+    value: TokenValue,
+    count: usize,
+}
+
+// Occurrences of literal values, keyed by group and rendered value
+#[derive(Default)]
+struct Tally {
+    entries: Vec<RawValue>,
+    index: HashMap<(&'static str, String), usize>,
+}
+
+impl Tally {
+    fn add(&mut self, root: &'static str, token_type: TokenType, value: TokenValue) {
+        let key = (root, render_value(&value).to_string());
+        match self.index.get(&key) {
+            Some(&i) => self.entries[i].count += 1,
+            None => {
+                self.index.insert(key, self.entries.len());
+                self.entries.push(RawValue { root, token_type, value, count: 1 });
+            }
+        }
+    }
+}
+
+// Count values that are neither bound to a variable nor set by a style
+fn tally_node(node: &Node, tally: &mut Tally) {
+    if !node.visible {
+        return;
+    }
+    let bound = |field: &str| {
+        node.bound_variables
+            .as_ref()
+            .and_then(|b| b.get(field))
+            .is_some()
+    };
+    let styled = |key: &str| node.styles.contains_key(key) || node.styles.contains_key(&format!("{}s", key));
+
+    for (key, paints) in [("fill", &node.fills), ("stroke", &node.strokes)] {
+        if styled(key) || bound(&format!("{}s", key)) {
+            continue;
+        }
+        for paint in paints {
+            if bound_variable_id(paint.bound_variables.as_ref(), "color").is_some() {
+                continue;
+            }
+            if let Some(color) = solid_color(paint) {
+                tally.add("color", TokenType::Color, TokenValue::Color(color));
+            }
+        }
+    }
+
+    if node.node_type != NodeType::Ellipse && !bound("cornerRadius") && !bound("topLeftRadius") {
+        let mut radii: Vec<f64> = node.rectangle_corner_radii.clone().unwrap_or_default();
+        radii.extend(node.corner_radius);
+        radii.retain(|radius| *radius > 0.0);
+        radii.sort_by(|a, b| a.total_cmp(b));
+        radii.dedup();
+        for radius in radii {
+            tally.add("radius", TokenType::Dimension, TokenValue::Dimension(radius));
+        }
+    }
+
+    if node.is_auto_layout() {
+        let mut spacing: Vec<f64> = [
+            ("itemSpacing", node.item_spacing),
+            ("paddingLeft", node.padding_left),
+            ("paddingRight", node.padding_right),
+            ("paddingTop", node.padding_top),
+            ("paddingBottom", node.padding_bottom),
+        ]
+        .into_iter()
+        .filter(|(field, _)| !bound(field))
+        .filter_map(|(_, value)| value)
+        .filter(|value| *value > 0.0)
+        .collect();
+        spacing.sort_by(|a, b| a.total_cmp(b));
+        spacing.dedup();
+        for value in spacing {
+            tally.add("spacing", TokenType::Dimension, TokenValue::Dimension(value));
+        }
+    }
+
+    if node.node_type == NodeType::Text
+        && !styled("text")
+        && let Some(style) = &node.style
+    {
+        tally.add("typography", TokenType::Typography, TokenValue::Typography(typography(style)));
+    }
+
+    if !styled("effect") {
+        let shadows = shadows(&node.effects);
+        if !shadows.is_empty() {
+            tally.add("shadow", TokenType::Shadow, TokenValue::Shadow(shadows));
+        }
+    }
+
+    for child in &node.children {
+        tally_node(child, tally);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| part.to_string()).collect()
+    }
+
+    fn file(document: Value) -> FigmaFile {
+        serde_json::from_value(json!({ "name": "Test", "document": document })).unwrap()
+    }
+
+    fn color_variable(id: &str, name: &str, value: Value) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "variableCollectionId": "c1",
+            "resolvedType": "COLOR",
+            "valuesByMode": { "m1": value },
+        })
+    }
+
+    fn variables(variables: Vec<Value>) -> LocalVariables {
+        let ids: Vec<Value> = variables.iter().map(|v| v["id"].clone()).collect();
+        let variables: serde_json::Map<String, Value> = variables
+            .into_iter()
+            .map(|v| (v["id"].as_str().unwrap().to_string(), v))
+            .collect();
+        serde_json::from_value(json!({
+            "variables": variables,
+            "variableCollections": {
+                "c1": {
+                    "id": "c1",
+                    "name": "Colors",
+                    "modes": [{ "modeId": "m1", "name": "Light" }],
+                    "defaultModeId": "m1",
+                    "variableIds": ids,
+                }
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn token_with_nested_tokens_moves_to_a_free_default() {
+        let red = json!({ "r": 1, "g": 0, "b": 0, "a": 1 });
+        let variables = variables(vec![
+            color_variable("v1", "Brand", red.clone()),
+            color_variable("v2", "Brand/Hover", red.clone()),
+            color_variable("v3", "Brand/Default", red.clone()),
+            color_variable("v4", "Link", json!({ "type": "VARIABLE_ALIAS", "id": "v1" })),
+        ]);
+        let set = extract_tokens(&file(json!({ "id": "0:0", "type": "DOCUMENT" })), Some(&variables), 3);
+
+        let paths: Vec<&Vec<String>> = set.tokens.iter().map(|t| &t.path).collect();
+        assert_eq!(
+            paths,
+            [
+                &path(&["color", "brand", "default-2"]),
+                &path(&["color", "brand", "hover"]),
+                &path(&["color", "brand", "default"]),
+                &path(&["color", "link"]),
+            ]
+        );
+        let link = set.find(&path(&["color", "link"])).unwrap();
+        assert_eq!(link.value, TokenValue::Alias(path(&["color", "brand", "default-2"])));
+    }
+
+    #[test]
+    fn variables_keep_their_modes_and_aliases() {
+        let mut variables = variables(vec![
+            color_variable("v1", "Colors/Primary", json!({ "r": 0, "g": 0, "b": 1, "a": 1 })),
+            color_variable("v2", "Button", json!({ "type": "VARIABLE_ALIAS", "id": "v1" })),
+        ]);
+        let collection = variables.variable_collections.get_mut("c1").unwrap();
+        collection.modes.push(serde_json::from_value(json!({ "modeId": "m2", "name": "Dark" })).unwrap());
+        variables.variables.get_mut("v1").unwrap().values_by_mode.insert(
+            "m2".to_string(),
+            serde_json::from_value(json!({ "r": 1, "g": 1, "b": 1, "a": 1 })).unwrap(),
+        );
+        let set = extract_tokens(&file(json!({ "id": "0:0", "type": "DOCUMENT" })), Some(&variables), 3);
+
+        let primary = set.find(&path(&["color", "primary"])).unwrap();
+        assert_eq!(primary.source, TokenSource::Variable);
+        assert_eq!(primary.default_mode.as_deref(), Some("Light"));
+        let modes: Vec<&str> = primary.modes.iter().map(|(mode, _)| mode.as_str()).collect();
+        assert_eq!(modes, ["Light", "Dark"]);
+
+        // v2 has no value in the Dark mode, so it only aliases in Light
+        let button = set.find(&path(&["color", "button"])).unwrap();
+        assert_eq!(button.value, TokenValue::Alias(path(&["color", "primary"])));
+        assert_eq!(button.modes.len(), 1);
+    }
+
+    #[test]
+    fn repeated_raw_values_become_tokens() {
+        let frame = |id: &str, color: Value| {
+            json!({
+                "id": id,
+                "type": "FRAME",
+                "fills": [{ "type": "SOLID", "color": color }],
+                "cornerRadius": 8,
+            })
+        };
+        let teal = json!({ "r": 0, "g": 0.5, "b": 0.5, "a": 1 });
+        let document = json!({
+            "id": "0:0",
+            "type": "DOCUMENT",
+            "children": [
+                frame("1:1", teal.clone()),
+                frame("1:2", teal.clone()),
+                frame("1:3", teal),
+                frame("1:4", json!({ "r": 1, "g": 0, "b": 0, "a": 1 })),
+            ],
+        });
+        let set = extract_tokens(&file(document), None, 3);
+
+        let paths: Vec<&Vec<String>> = set.tokens.iter().map(|t| &t.path).collect();
+        assert_eq!(paths, [&path(&["color", "008080"]), &path(&["radius", "8"])]);
+        let teal = set.find(&path(&["color", "008080"])).unwrap();
+        assert_eq!(teal.source, TokenSource::Raw);
+        assert_eq!(teal.occurrences, 3);
+        assert_eq!(set.find(&path(&["radius", "8"])).unwrap().occurrences, 4);
+    }
+
+    #[test]
+    fn config_values_become_component_tokens() {
+        let config = HashMap::from([
+            ("backgroundColor".to_string(), "#ff0000".to_string()),
+            ("padding".to_string(), "12px".to_string()),
+            ("cursor".to_string(), "pointer".to_string()),
+        ]);
+        let set = tokens_from_config("CustomButton", &config);
+
+        let paths: Vec<&Vec<String>> = set.tokens.iter().map(|t| &t.path).collect();
+        assert_eq!(
+            paths,
+            [
+                &path(&["color", "custom-button", "background-color"]),
+                &path(&["spacing", "custom-button", "padding"]),
+            ]
+        );
+        assert_eq!(set.tokens[1].value, TokenValue::Dimension(12.0));
+    }
+}
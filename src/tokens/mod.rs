@@ -0,0 +1,8 @@
+pub mod routes;
+pub mod model;
+pub mod extract;
+pub mod dtcg;
+pub mod css;
+pub mod theme;
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,278 @@
+use crate::figma::models::Color;
+
+// Token types from the W3C Design Tokens format that we emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenType {
+    Color,
+    Dimension,
+    Number,
+    FontFamily,
+    FontWeight,
+    Typography,
+    Shadow,
+}
+
+impl TokenType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenType::Color => "color",
+            TokenType::Dimension => "dimension",
+            TokenType::Number => "number",
+            TokenType::FontFamily => "fontFamily",
+            TokenType::FontWeight => "fontWeight",
+            TokenType::Typography => "typography",
+            TokenType::Shadow => "shadow",
+        }
+    }
+}
+
+// Where in the Figma file a token was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenSource {
+    Variable,
+    Style,
+    // A literal value repeated often enough to deserve a name
+    Raw,
+    // A value from a create-component request's config map
+    Config,
+}
+
+impl TokenSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenSource::Variable => "variable",
+            TokenSource::Style => "style",
+            TokenSource::Raw => "raw",
+            TokenSource::Config => "config",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenValue {
+    // Reference to another token by its path
+    Alias(Vec<String>),
+    Color(Color),
+    // Pixels
+    Dimension(f64),
+    // Plain numbers, also used for font weights
+    Number(f64),
+    FontFamily(String),
+    Typography(Typography),
+    Shadow(Vec<Shadow>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    pub font_family: Option<String>,
+    pub font_size: Option<f64>,
+    pub font_weight: Option<f64>,
+    pub line_height: Option<f64>,
+    pub letter_spacing: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shadow {
+    pub color: Color,
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub blur: f64,
+    pub spread: f64,
+    pub inset: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    // Group names followed by the token name, e.g. ["color", "primary"]
+    pub path: Vec<String>,
+    pub token_type: TokenType,
+    // Value in the default mode
+    pub value: TokenValue,
+    pub description: Option<String>,
+    // Value per variable mode name; empty for single-mode tokens
+    pub modes: Vec<(String, TokenValue)>,
+    // Name of the mode `value` comes from
+    pub default_mode: Option<String>,
+    pub source: TokenSource,
+    // How often a raw value was seen
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenSet {
+    pub tokens: Vec<Token>,
+}
+
+impl TokenSet {
+    pub fn find(&self, path: &[String]) -> Option<&Token> {
+        self.tokens.iter().find(|token| token.path == path)
+    }
+
+    // Follow aliases to a literal value; None for broken or looping aliases
+    pub fn resolve<'a>(&'a self, value: &'a TokenValue) -> Option<&'a TokenValue> {
+        self.resolve_in(value, None)
+    }
+
+    // Same as resolve, reading every token along the way in a theme
+    pub fn resolve_in<'a>(&'a self, value: &'a TokenValue, theme: Option<&str>) -> Option<&'a TokenValue> {
+        let mut current = value;
+        for _ in 0..=self.tokens.len() {
+            match current {
+                TokenValue::Alias(path) => current = self.value_in(self.find(path)?, theme),
+                literal => return Some(literal),
+            }
+        }
+        None
+    }
+
+    // A token's value in a theme. Tokens without that mode keep their default.
+    pub fn value_in<'a>(&self, token: &'a Token, theme: Option<&str>) -> &'a TokenValue {
+        theme
+            .and_then(|theme| token.modes.iter().find(|(mode, _)| token_segment(mode) == theme))
+            .map(|(_, value)| value)
+            .unwrap_or(&token.value)
+    }
+
+    // Themes are the variable modes, named like tokens: "Dark Mode" is "dark-mode"
+    pub fn themes(&self) -> Vec<String> {
+        let mut themes: Vec<String> = Vec::new();
+        for (mode, _) in self.tokens.iter().flat_map(|token| &token.modes) {
+            let theme = token_segment(mode);
+            if !themes.contains(&theme) {
+                themes.push(theme);
+            }
+        }
+        themes
+    }
+
+    // Theme whose values are the defaults, i.e. the mode the designer had selected
+    pub fn default_theme(&self) -> Option<String> {
+        self.tokens
+            .iter()
+            .find_map(|token| token.default_mode.as_deref())
+            .map(token_segment)
+    }
+
+    // Make a theme the default, so :root and the resolved values use it
+    pub fn with_default_theme(mut self, theme: &str) -> Result<Self, String> {
+        let themes = self.themes();
+        if themes.is_empty() {
+            return Err(format!("Unknown theme: {}. The tokens have no variable modes", theme));
+        }
+        if !themes.iter().any(|t| t == theme) {
+            return Err(format!(
+                "Unknown theme: {}. Available themes: {}",
+                theme,
+                themes.join(", ")
+            ));
+        }
+        for token in &mut self.tokens {
+            if let Some((mode, value)) = token.modes.iter().find(|(mode, _)| token_segment(mode) == theme) {
+                token.value = value.clone();
+                token.default_mode = Some(mode.clone());
+            }
+        }
+        Ok(self)
+    }
+}
+
+// Token names are kebab-case so they map directly onto CSS custom
+// properties: "Primary Blue" and "primaryBlue" both become "primary-blue"
+pub fn token_segment(name: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && previous_lower && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c.to_ascii_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    if words.is_empty() {
+        "token".to_string()
+    } else {
+        words.join("-")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::test_support::{color, path, themed, token, token_set};
+
+    #[test]
+    fn themes_are_the_mode_names_as_tokens_in_first_seen_order() {
+        let mut set = token_set();
+        set.tokens.push(themed(
+            &["color", "accent"],
+            TokenType::Color,
+            &[("High contrast", color(0x000000)), ("DarkMode", color(0x222222))],
+        ));
+        assert_eq!(set.themes(), ["light", "dark-mode", "high-contrast"]);
+        assert_eq!(set.default_theme().as_deref(), Some("light"));
+        assert!(TokenSet::default().themes().is_empty());
+    }
+
+    #[test]
+    fn aliases_resolve_within_a_theme() {
+        let set = token_set();
+        let primary = set.find(&path(&["color", "primary"])).unwrap();
+        assert_eq!(set.resolve(&primary.value), Some(&color(0x0055ff)));
+        assert_eq!(set.resolve_in(&primary.value, Some("dark-mode")), Some(&color(0x002288)));
+        // Tokens without the mode keep their default
+        let spacing = set.find(&path(&["spacing", "sm"])).unwrap();
+        assert_eq!(set.value_in(spacing, Some("dark-mode")), &TokenValue::Dimension(8.0));
+    }
+
+    #[test]
+    fn looping_and_broken_aliases_do_not_resolve() {
+        let set = TokenSet {
+            tokens: vec![
+                token(&["a"], TokenType::Color, TokenValue::Alias(path(&["b"]))),
+                token(&["b"], TokenType::Color, TokenValue::Alias(path(&["a"]))),
+                token(&["c"], TokenType::Color, TokenValue::Alias(path(&["missing"]))),
+            ],
+        };
+        assert_eq!(set.resolve(&set.tokens[0].value), None);
+        assert_eq!(set.resolve(&set.tokens[2].value), None);
+    }
+
+    #[test]
+    fn a_chosen_theme_becomes_the_default() {
+        let set = token_set().with_default_theme("dark-mode").unwrap();
+        let blue = set.find(&path(&["color", "blue"])).unwrap();
+        assert_eq!(blue.value, color(0x002288));
+        assert_eq!(blue.default_mode.as_deref(), Some("Dark Mode"));
+        assert_eq!(set.default_theme().as_deref(), Some("dark-mode"));
+        assert_eq!(set.find(&path(&["spacing", "sm"])).unwrap().value, TokenValue::Dimension(8.0));
+    }
+
+    #[test]
+    fn unknown_themes_are_rejected() {
+        let error = token_set().with_default_theme("sepia").unwrap_err();
+        assert_eq!(error, "Unknown theme: sepia. Available themes: light, dark-mode");
+        let error = TokenSet::default().with_default_theme("dark").unwrap_err();
+        assert_eq!(error, "Unknown theme: dark. The tokens have no variable modes");
+    }
+
+    #[test]
+    fn names_become_kebab_case_segments() {
+        assert_eq!(token_segment("Primary Blue"), "primary-blue");
+        assert_eq!(token_segment("primaryBlue"), "primary-blue");
+        assert_eq!(token_segment("Dark Mode / 2"), "dark-mode-2");
+        assert_eq!(token_segment("HTML5"), "html5");
+        assert_eq!(token_segment(" / "), "token");
+    }
+}
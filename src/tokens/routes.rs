@@ -0,0 +1,60 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use crate::tokens::dtcg::to_dtcg;
+use crate::tokens::extract::{tokens_for_file, DEFAULT_MIN_OCCURRENCES};
+
+// Struct for query parameters
+#[derive(Deserialize)]
+pub struct TokenParams {
+    format: Option<String>,           // only "dtcg" for now
+    min_occurrences: Option<usize>,   // how often a raw value must repeat
+}
+
+// Struct for returning errors; successful responses are the token document
+#[derive(Serialize)]
+pub struct ApiResponse {
+    file_key: String,
+    message: String,
+    status: String,
+}
+
+// GET handler that exports the design tokens of the stored file
+#[get("/{file_key}")]
+pub async fn get_tokens(
+    path: web::Path<String>,
+    query: web::Query<TokenParams>,
+    db: web::Data<crate::db::mongo::MongoDb>,
+) -> impl Responder {
+    let file_key = path.into_inner();
+
+    let format = query.format.as_deref().unwrap_or("dtcg");
+    if format != "dtcg" {
+        return HttpResponse::BadRequest().json(ApiResponse {
+            file_key,
+            message: format!("Unsupported token format: {}. Supported formats: dtcg", format),
+            status: "error".to_string(),
+        });
+    }
+
+    let min_occurrences = query.min_occurrences.unwrap_or(DEFAULT_MIN_OCCURRENCES);
+    match tokens_for_file(&db, &file_key, min_occurrences).await {
+        Ok(Some(tokens)) => HttpResponse::Ok().json(to_dtcg(&tokens)),
+        Ok(None) => HttpResponse::NotFound().json(ApiResponse {
+            message: format!(
+                "Figma file {} is not stored. Fetch it with /api/figma/getFile first",
+                file_key
+            ),
+            file_key,
+            status: "error".to_string(),
+        }),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse {
+            file_key,
+            message: format!("Database error: {}", e),
+            status: "error".to_string(),
+        }),
+    }
+}
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_tokens);
+}
@@ -0,0 +1,67 @@
+// Fixtures shared by the token tests
+use crate::figma::models::Color;
+use crate::tokens::model::{Token, TokenSet, TokenSource, TokenType, TokenValue, Typography};
+
+pub fn path(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|part| part.to_string()).collect()
+}
+
+pub fn token(parts: &[&str], token_type: TokenType, value: TokenValue) -> Token {
+    Token {
+        path: path(parts),
+        token_type,
+        value,
+        description: None,
+        modes: Vec::new(),
+        default_mode: None,
+        source: TokenSource::Variable,
+        occurrences: 0,
+    }
+}
+
+pub fn color(hex: u32) -> TokenValue {
+    let channel = |shift: u32| ((hex >> shift) & 0xff) as f64 / 255.0;
+    TokenValue::Color(Color { r: channel(16), g: channel(8), b: channel(0), a: 1.0 })
+}
+
+// A token with a value per mode; the first mode is the default
+pub fn themed(parts: &[&str], token_type: TokenType, modes: &[(&str, TokenValue)]) -> Token {
+    let mut token = token(parts, token_type, modes[0].1.clone());
+    token.modes = modes.iter().map(|(mode, value)| (mode.to_string(), value.clone())).collect();
+    token.default_mode = Some(modes[0].0.to_string());
+    token
+}
+
+// Primitive colors with light and dark modes, a semantic alias, a spacing
+// scale and a typography style with an alias of its own
+pub fn token_set() -> TokenSet {
+    TokenSet {
+        tokens: vec![
+            themed(
+                &["color", "blue"],
+                TokenType::Color,
+                &[("Light", color(0x0055ff)), ("Dark Mode", color(0x002288))],
+            ),
+            themed(
+                &["color", "surface"],
+                TokenType::Color,
+                &[("Light", color(0xffffff)), ("Dark Mode", color(0x111111))],
+            ),
+            token(&["color", "primary"], TokenType::Color, TokenValue::Alias(path(&["color", "blue"]))),
+            token(&["spacing", "sm"], TokenType::Dimension, TokenValue::Dimension(8.0)),
+            token(&["font-family", "body"], TokenType::FontFamily, TokenValue::FontFamily("Inter Tight".to_string())),
+            token(
+                &["typography", "heading"],
+                TokenType::Typography,
+                TokenValue::Typography(Typography {
+                    font_family: Some("Inter".to_string()),
+                    font_size: Some(24.0),
+                    font_weight: Some(700.0),
+                    line_height: Some(32.0),
+                    letter_spacing: None,
+                }),
+            ),
+            token(&["typography", "title"], TokenType::Typography, TokenValue::Alias(path(&["typography", "heading"]))),
+        ],
+    }
+}
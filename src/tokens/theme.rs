@@ -0,0 +1,74 @@
+use crate::generator::node_tree::js_string;
+use crate::tokens::css::{ts_value, TsGroup, HEADER};
+use crate::tokens::model::TokenSet;
+
+// Name used when the file has no multi-mode variable collections
+pub const FALLBACK_THEME: &str = "default";
+
+// themes.ts: one object of resolved token values per theme, shaped like
+// `tokens` in tokens.ts
+pub fn to_themes_ts(set: &TokenSet) -> String {
+    let mut themes = set.themes();
+    if themes.is_empty() {
+        themes.push(FALLBACK_THEME.to_string());
+    }
+    let default_theme = set
+        .default_theme()
+        .unwrap_or_else(|| themes[0].clone());
+
+    let mut out = format!("// {}\n\n", HEADER);
+    out.push_str("// Token values per theme, from the modes of Figma variable collections\n");
+    out.push_str("export const themes = {\n");
+    for theme in &themes {
+        let mut values = TsGroup::default();
+        for token in &set.tokens {
+            let value = set.value_in(token, Some(theme));
+            if let Some(value) = set.resolve_in(value, Some(theme)) {
+                values.insert(&token.path, ts_value(value));
+            }
+        }
+        out.push_str(&format!("  {}: {},\n", js_string(theme), values.render(1)));
+    }
+    out.push_str("};\n\n");
+    out.push_str("export type ThemeName = keyof typeof themes;\n");
+    out.push_str("export type Theme = (typeof themes)[ThemeName];\n\n");
+    out.push_str(&format!("export const defaultTheme: ThemeName = {};\n", js_string(&default_theme)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::model::{TokenType, TokenValue};
+    use crate::tokens::test_support::{token, token_set};
+
+    #[test]
+    fn every_theme_gets_its_resolved_values() {
+        let ts = to_themes_ts(&token_set());
+        assert!(ts.contains(
+            "export const themes = {\n  \
+             'light': {\n    color: {\n      blue: '#0055ff',\n      surface: '#ffffff',\n      primary: '#0055ff',\n    },\n"
+        ));
+        // Aliases are followed inside the theme
+        assert!(ts.contains(
+            "  'dark-mode': {\n    color: {\n      blue: '#002288',\n      surface: '#111111',\n      primary: '#002288',\n    },\n    \
+             spacing: {\n      sm: '8px',\n    },\n"
+        ));
+        assert!(ts.contains("export type ThemeName = keyof typeof themes;\n"));
+        assert!(ts.ends_with("export const defaultTheme: ThemeName = 'light';\n"));
+    }
+
+    #[test]
+    fn the_default_theme_follows_the_chosen_mode() {
+        let set = token_set().with_default_theme("dark-mode").unwrap();
+        assert!(to_themes_ts(&set).ends_with("export const defaultTheme: ThemeName = 'dark-mode';\n"));
+    }
+
+    #[test]
+    fn sets_without_modes_have_a_single_fallback_theme() {
+        let set = TokenSet { tokens: vec![token(&["spacing", "sm"], TokenType::Dimension, TokenValue::Dimension(4.0))] };
+        let ts = to_themes_ts(&set);
+        assert!(ts.contains("export const themes = {\n  'default': {\n    spacing: {\n      sm: '4px',\n    },\n  },\n};\n"));
+        assert!(ts.ends_with("export const defaultTheme: ThemeName = 'default';\n"));
+    }
+}